@@ -0,0 +1,349 @@
+extern crate sudokugen;
+extern crate termion;
+
+use std::env;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+use sudokugen::board::Conflict;
+use sudokugen::game::GameSession;
+use sudokugen::generator::random_gen::RandomSudoku;
+use sudokugen::generator::{Difficulty, Generator};
+use sudokugen::solver::least_options::LeastOptionsSolver;
+use sudokugen::solver::logical::{Hint, HintLevel, LogicalSolver};
+use sudokugen::solver::Solver;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+use termion::{clear, color, cursor, style};
+
+static HELP: &str = "arrows/hjkl move  1-9 enter  0/x erase  m marks  ? hint  \
+                     u undo  r redo  c check  s solve  p pause  n new  q quit";
+
+/// Interval between checks for key presses, and timer updates.
+const TICK: Duration = Duration::from_millis(50);
+
+/// Shows the terminal cursor again when dropped, on every exit path.
+struct CursorGuard;
+
+impl Drop for CursorGuard {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = write!(out, "{}", cursor::Show);
+        let _ = out.flush();
+    }
+}
+
+/// Game in the terminal, with the state of the screen around it.
+struct App {
+    session: GameSession,
+    difficulty: Difficulty,
+    cursor: (usize, usize),
+    marking: bool,
+    hint: Option<(Hint, HintLevel)>,
+    message: String,
+}
+
+impl App {
+    fn new(difficulty: Difficulty) -> Result<App, sudokugen::Error> {
+        Ok(App {
+            session: new_game(difficulty)?,
+            difficulty,
+            cursor: (0, 0),
+            marking: false,
+            hint: None,
+            message: String::from("New game"),
+        })
+    }
+
+    /// Handles key press. Returns false when the player quits.
+    ///
+    /// While paused, only resuming and quitting are allowed.
+    fn handle(&mut self, key: Key) -> bool {
+        let (row, col) = self.cursor;
+        let result = match key {
+            Key::Char('q') | Key::Ctrl('c') => return false,
+            Key::Char('p') => {
+                if self.session.is_paused() {
+                    self.session.resume();
+                    self.message.clear();
+                } else {
+                    self.session.pause();
+                    self.message = String::from("Paused, press p to resume");
+                }
+                Ok(())
+            }
+            _ if self.session.is_paused() => return true,
+            Key::Left | Key::Char('h') => self.move_cursor(0, 8),
+            Key::Right | Key::Char('l') => self.move_cursor(0, 1),
+            Key::Up | Key::Char('k') => self.move_cursor(8, 0),
+            Key::Down | Key::Char('j') => self.move_cursor(1, 0),
+            Key::Char(c @ '1'..='9') => {
+                let value = c.to_digit(10).unwrap() as u8;
+                if self.marking {
+                    self.session.toggle_candidate(row, col, value)
+                } else {
+                    self.session.place(row, col, value)
+                }
+            }
+            Key::Char('0') | Key::Char('x') | Key::Backspace | Key::Delete => {
+                self.session.erase(row, col)
+            }
+            Key::Char('m') => {
+                self.marking = !self.marking;
+                Ok(())
+            }
+            Key::Char('?') => {
+                self.show_hint();
+                Ok(())
+            }
+            Key::Char('u') => {
+                if self.session.undo().is_none() {
+                    self.message = String::from("Nothing to undo");
+                }
+                Ok(())
+            }
+            Key::Char('r') => {
+                if self.session.redo().is_none() {
+                    self.message = String::from("Nothing to redo");
+                }
+                Ok(())
+            }
+            Key::Char('c') => {
+                self.check();
+                Ok(())
+            }
+            Key::Char('s') => self.solve(),
+            Key::Char('n') => match new_game(self.difficulty) {
+                Ok(session) => {
+                    self.session = session;
+                    self.message = String::from("New game");
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            self.message = e.to_string();
+        }
+        if key != Key::Char('?') {
+            self.hint = None;
+        }
+        true
+    }
+
+    fn move_cursor(&mut self, rows: usize, cols: usize) -> Result<(), sudokugen::Error> {
+        let (row, col) = self.cursor;
+        self.cursor = ((row + rows) % 9, (col + cols) % 9);
+        Ok(())
+    }
+
+    /// Shows more of the next hint each time it is asked for.
+    fn show_hint(&mut self) {
+        let next = match self.hint.take() {
+            Some((hint, HintLevel::Region)) => Some((hint, HintLevel::Technique)),
+            Some((hint, _)) => Some((hint, HintLevel::Placement)),
+            None => LogicalSolver::new()
+                .hint(self.session.board())
+                .map(|hint| (hint, HintLevel::Region)),
+        };
+
+        self.message = match next {
+            Some((ref hint, level)) => hint.describe(level),
+            None => String::from("No hint available"),
+        };
+        self.hint = next;
+    }
+
+    /// Counts placed values that differ from the solution.
+    fn check(&mut self) {
+        let puzzle = &self.session.puzzle().board;
+        let board = self.session.board();
+        self.message = match LeastOptionsSolver::new().solve(puzzle) {
+            Ok(solution) => {
+                let wrong = (0..81)
+                    .filter(|&index| {
+                        board.values[index] > 0
+                            && board.values[index] != solution.board.values[index]
+                    })
+                    .count();
+                match wrong {
+                    0 => String::from("No mistakes so far"),
+                    1 => String::from("1 value is wrong"),
+                    n => format!("{} values are wrong", n),
+                }
+            }
+            Err(e) => e.to_string(),
+        };
+    }
+
+    /// Places the solution in every cell that does not hold it yet.
+    fn solve(&mut self) -> Result<(), sudokugen::Error> {
+        let solution = LeastOptionsSolver::new().solve(&self.session.puzzle().board)?;
+        for index in 0..81 {
+            let value = solution.board.values[index];
+            if self.session.board().values[index] != value {
+                self.session.place(index / 9, index % 9, value)?;
+            }
+        }
+        self.message = String::from("Solved");
+        Ok(())
+    }
+
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let board = self.session.board();
+        let conflicts = board.conflicts();
+        let elapsed = self.session.elapsed().as_secs();
+        // Overwrite previous screen line by line, clearing what is left of it
+        let eol = format!("{}\r\n", clear::UntilNewline);
+
+        write!(out, "{}", cursor::Goto(1, 1))?;
+        write!(
+            out,
+            "sudokugen  {}  {:02}:{:02}  Mistakes: {}{}{}",
+            self.difficulty,
+            elapsed / 60,
+            elapsed % 60,
+            self.session.mistakes(),
+            if self.session.is_paused() {
+                "  PAUSED"
+            } else {
+                ""
+            },
+            eol
+        )?;
+
+        for row in 0..9 {
+            if row % 3 == 0 {
+                write!(out, "+-------+-------+-------+{}", eol)?;
+            }
+            for col in 0..9 {
+                if col % 3 == 0 {
+                    write!(out, "| ")?;
+                }
+                let index = row * 9 + col;
+                let text = match board.values[index] {
+                    0 if board.get_marks(row, col).is_empty() => '.',
+                    0 => '*',
+                    value => std::char::from_digit(u32::from(value), 10).unwrap(),
+                };
+
+                if (row, col) == self.cursor {
+                    write!(out, "{}", style::Invert)?;
+                }
+                if board.clues[index] {
+                    write!(out, "{}", style::Bold)?;
+                } else if is_in_conflict(&conflicts, row, col) {
+                    write!(out, "{}", color::Fg(color::Red))?;
+                } else if board.values[index] > 0 {
+                    write!(out, "{}", color::Fg(color::Blue))?;
+                }
+                write!(out, "{}{} ", text, style::Reset)?;
+            }
+            write!(out, "|{}", eol)?;
+        }
+        write!(out, "+-------+-------+-------+{}", eol)?;
+
+        let (row, col) = self.cursor;
+        let marks: Vec<String> = board
+            .get_marks(row, col)
+            .iter()
+            .map(|value| value.to_string())
+            .collect();
+        write!(
+            out,
+            "Mode: {}  Marks: {}{}",
+            if self.marking { "marks" } else { "values" },
+            marks.join(" "),
+            eol
+        )?;
+
+        if self.session.is_complete() {
+            write!(
+                out,
+                "Completed in {:02}:{:02} with {} mistakes{}",
+                elapsed / 60,
+                elapsed % 60,
+                self.session.mistakes(),
+                eol
+            )?;
+        }
+        for line in self.message.lines() {
+            write!(out, "{}{}", line, eol)?;
+        }
+        write!(out, "{}{}{}{}", eol, HELP, eol, clear::AfterCursor)?;
+        out.flush()
+    }
+}
+
+fn is_in_conflict(conflicts: &[Conflict], row: usize, col: usize) -> bool {
+    conflicts
+        .iter()
+        .any(|conflict| conflict.cells.0 == (row, col) || conflict.cells.1 == (row, col))
+}
+
+fn new_game(difficulty: Difficulty) -> Result<GameSession, sudokugen::Error> {
+    let puzzle = RandomSudoku::new(LeastOptionsSolver::new())
+        .difficulty(difficulty)
+        .run()?;
+    GameSession::new(puzzle)
+}
+
+fn parse_difficulty(name: &str) -> Option<Difficulty> {
+    match name.to_lowercase().as_str() {
+        "easy" => Some(Difficulty::Easy),
+        "medium" => Some(Difficulty::Medium),
+        "hard" => Some(Difficulty::Hard),
+        "evil" => Some(Difficulty::Evil),
+        _ => None,
+    }
+}
+
+fn run(difficulty: Difficulty) -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = App::new(difficulty)?;
+    let mut keys = termion::async_stdin().keys();
+    let mut screen = AlternateScreen::from(io::stdout().into_raw_mode()?);
+    let _cursor = CursorGuard;
+    write!(screen, "{}", cursor::Hide)?;
+
+    let mut changed = true;
+    let mut shown_secs = 0;
+    loop {
+        // Redraw after key presses, and when the timer ticks over
+        let secs = app.session.elapsed().as_secs();
+        if changed || secs != shown_secs {
+            app.draw(&mut screen)?;
+            changed = false;
+            shown_secs = secs;
+        }
+
+        thread::sleep(TICK);
+        for key in &mut keys {
+            if !app.handle(key?) {
+                return Ok(());
+            }
+            changed = true;
+        }
+    }
+}
+
+fn main() {
+    let difficulty = match env::args().nth(1) {
+        None => Difficulty::Easy,
+        Some(name) => match parse_difficulty(&name) {
+            Some(difficulty) => difficulty,
+            None => {
+                eprintln!("Usage: sudokugen-tui [easy|medium|hard|evil]");
+                std::process::exit(2);
+            }
+        },
+    };
+
+    if let Err(e) = run(difficulty) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -1,51 +1,540 @@
-extern crate rand;
+extern crate env_logger;
 extern crate sudokugen;
+
 use std::env;
-use std::io;
-#[macro_use]
-extern crate log;
-extern crate env_logger;
-use sudokugen::generator::random_gen::*;
-use sudokugen::generator::{Difficulty, Generator};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::process;
+use sudokugen::board::SudokuBoard;
+use sudokugen::formats::grid::{self, GridFormat, GridReader};
+use sudokugen::formats::line::{Entry, PuzzleReader, PuzzleWriter};
+use sudokugen::generator::batch::generate_batch;
+use sudokugen::generator::random_gen::{DifficultyMeasure, RandomSudoku, Symmetry};
+use sudokugen::generator::{Difficulty, Metadata};
+use sudokugen::rating::Rater;
+use sudokugen::solver::bitboard::BitboardSolver;
 use sudokugen::solver::least_options::LeastOptionsSolver;
-use sudokugen::solver::Solver;
-
-fn generate_puzzle() -> Result<(), Box<std::error::Error>> {
-    // Provide a Solver to generate puzzle
-    let solver = LeastOptionsSolver::new();
-    // Create puzzle generator of specified difficulty
-    let mut generator = RandomSudoku::new(solver).difficulty(Difficulty::Evil);
-    // Create a puzzle
-    let puzzle = generator.run()?;
-    // Calculate number of clues
-    let num_clues = puzzle.board.clues.iter().filter(|&c| *c).count();
-    // Print out number of clues and the board
-    println!("\n# of clues: {}\n", num_clues);
-    println!("Verified board:\n{}", puzzle.board);
-
-    // Create puzzle as string of numbers, e.g. "0100400..."
-    let board_values = puzzle
-        .board
-        .values
-        .iter()
-        .fold(String::new(), |s, &val| format!("{}{:01}", s, val));
-    // Print out puzzle as numbers
-    println!("Puzzle: {}", board_values);
-
-    // Solve the board, why??
-    LeastOptionsSolver::new().solve(&mut puzzle.board.clone())?;
-    // Read Enter from keyboard input
-    let mut input = String::new();
-    let _ = io::stdin().read_line(&mut input);
-    Ok(())
-}
-
-// TODO: Support arguments for difficulty, logging and seed
+use sudokugen::solver::logical::cell_name;
+use sudokugen::solver::{Contradiction, Solver, Verification};
+
+/// Generator runs that may fail in a row before `generate` gives up.
+const MAX_FAILURES: u32 = 20;
+
+static USAGE: &str = "\
+Usage: sudokugen <command> [options] [file...]
+
+Commands:
+    generate    Generate new puzzles
+    solve       Print solution of each puzzle
+    verify      Check that each puzzle has exactly one solution
+    rate        Rate difficulty of each puzzle by techniques needed
+    convert     Convert puzzles to another format
+
+Puzzles are read from the files given, or from stdin if there are none,
+and results are written to stdout. Set RUST_LOG=debug for progress logs.
+
+Options:
+    -d, --difficulty <level>    easy, medium, hard or evil (generate, default easy)
+    -m, --measure <measure>     branches or techniques (generate, default branches)
+    -y, --symmetry <symmetry>   none, rotational180, rotational90, horizontal, vertical,
+                                diagonal, antidiagonal or full (generate, default none)
+    -c, --clues <n|min-max>     number of clues, e.g. 24-28 (generate)
+        --minimal               only keep clues needed for a unique solution; needs
+                                no symmetry and hard or evil (generate)
+    -s, --seed <n>              seed of first puzzle, counting up (generate)
+    -n, --count <n>             number of puzzles, none equivalent to another
+                                (generate, default 1)
+    -t, --threads <n>           number of threads (generate, default 1)
+    -f, --format <format>       output format: line, sdk or ss (default line)
+    -i, --input <format>        input format: line, sdk or ss
+                                (default by file extension, line for stdin)
+    -h, --help                  print this help";
+
+/// Layout of puzzles read or written.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// One puzzle per line, as in `.sdm` collections.
+    Line,
+    /// One puzzle per file, in grid layout.
+    Grid(GridFormat),
+}
+
+impl Format {
+    fn parse(name: &str) -> Option<Format> {
+        match name {
+            "line" | "sdm" => Some(Format::Line),
+            "sdk" => Some(Format::Grid(GridFormat::Sdk)),
+            "ss" => Some(Format::Grid(GridFormat::Ss)),
+            _ => None,
+        }
+    }
+
+    /// Guesses format of a file from its extension.
+    fn of_path(path: &str) -> Format {
+        Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| Format::parse(&ext.to_lowercase()))
+            .unwrap_or(Format::Line)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Command {
+    Generate,
+    Solve,
+    Verify,
+    Rate,
+    Convert,
+}
+
+/// Command line arguments.
+#[derive(Clone)]
+struct Args {
+    command: Command,
+    difficulty: Difficulty,
+    measure: DifficultyMeasure,
+    symmetry: Symmetry,
+    minimal: bool,
+    clues: RangeInclusive<usize>,
+    seed: Option<u32>,
+    count: u32,
+    threads: usize,
+    format: Format,
+    input: Option<Format>,
+    files: Vec<String>,
+}
+
+impl Args {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+        let command = match args.next().as_deref() {
+            Some("generate") => Command::Generate,
+            Some("solve") => Command::Solve,
+            Some("verify") => Command::Verify,
+            Some("rate") => Command::Rate,
+            Some("convert") => Command::Convert,
+            Some(other) => return Err(format!("Unknown command '{}'", other)),
+            None => return Err(String::from("Missing command")),
+        };
+
+        let mut parsed = Args {
+            command,
+            difficulty: Difficulty::Easy,
+            measure: DifficultyMeasure::Branches,
+            symmetry: Symmetry::None,
+            minimal: false,
+            clues: 0..=81,
+            seed: None,
+            count: 1,
+            threads: 1,
+            format: Format::Line,
+            input: None,
+            files: Vec::new(),
+        };
+
+        while let Some(arg) = args.next() {
+            // Accept both `--seed 42` and `--seed=42`
+            let (name, inline) = match arg.find('=') {
+                Some(pos) if arg.starts_with("--") => (&arg[..pos], Some(&arg[pos + 1..])),
+                _ => (arg.as_str(), None),
+            };
+            if !name.starts_with('-') || name == "-" {
+                parsed.files.push(arg.clone());
+                continue;
+            }
+
+            if name == "--minimal" {
+                parsed.minimal = true;
+                continue;
+            }
+
+            let mut value = || {
+                inline
+                    .map(String::from)
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("Missing value for {}", name))
+            };
+            match name {
+                "-d" | "--difficulty" => {
+                    let value = value()?;
+                    parsed.difficulty = parse_difficulty(&value)
+                        .ok_or_else(|| format!("Unknown difficulty '{}'", value))?;
+                }
+                "-m" | "--measure" => {
+                    parsed.measure = match value()?.as_str() {
+                        "branches" => DifficultyMeasure::Branches,
+                        "techniques" => DifficultyMeasure::Techniques,
+                        other => return Err(format!("Unknown measure '{}'", other)),
+                    };
+                }
+                "-y" | "--symmetry" => {
+                    let value = value()?;
+                    parsed.symmetry = parse_symmetry(&value)
+                        .ok_or_else(|| format!("Unknown symmetry '{}'", value))?;
+                }
+                "-c" | "--clues" => {
+                    let value = value()?;
+                    parsed.clues =
+                        parse_clues(&value).ok_or_else(|| format!("Invalid clues '{}'", value))?;
+                }
+                "-s" | "--seed" => {
+                    let value = value()?;
+                    parsed.seed = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid seed '{}'", value))?,
+                    );
+                }
+                "-n" | "--count" => {
+                    let value = value()?;
+                    parsed.count = value
+                        .parse()
+                        .map_err(|_| format!("Invalid count '{}'", value))?;
+                }
+                "-t" | "--threads" => {
+                    let value = value()?;
+                    parsed.threads = match value.parse() {
+                        Ok(threads) if threads > 0 => threads,
+                        _ => return Err(format!("Invalid threads '{}'", value)),
+                    };
+                }
+                "-f" | "--format" => {
+                    let value = value()?;
+                    parsed.format = Format::parse(&value)
+                        .ok_or_else(|| format!("Unknown format '{}'", value))?;
+                }
+                "-i" | "--input" => {
+                    let value = value()?;
+                    parsed.input = Some(
+                        Format::parse(&value)
+                            .ok_or_else(|| format!("Unknown format '{}'", value))?,
+                    );
+                }
+                _ => return Err(format!("Unknown option '{}'", name)),
+            }
+        }
+
+        if command == Command::Generate {
+            if !parsed.files.is_empty() {
+                return Err(String::from("generate does not read files"));
+            }
+            parsed.generator(0).check().map_err(|e| e.to_string())?;
+        }
+        Ok(parsed)
+    }
+
+    /// Returns generator for the options, with seed based on `attempt`.
+    fn generator(&self, attempt: u32) -> RandomSudoku {
+        let generator = RandomSudoku::new(LeastOptionsSolver::new())
+            .difficulty(self.difficulty)
+            .measure(self.measure)
+            .symmetry(self.symmetry)
+            .minimal(self.minimal)
+            .clues(self.clues.clone());
+        match self.seed {
+            Some(seed) => generator.seed(seed.wrapping_add(attempt)),
+            None => generator,
+        }
+    }
+}
+
+fn parse_difficulty(name: &str) -> Option<Difficulty> {
+    match name.to_lowercase().as_str() {
+        "easy" => Some(Difficulty::Easy),
+        "medium" => Some(Difficulty::Medium),
+        "hard" => Some(Difficulty::Hard),
+        "evil" => Some(Difficulty::Evil),
+        _ => None,
+    }
+}
+
+fn parse_symmetry(name: &str) -> Option<Symmetry> {
+    match name.to_lowercase().as_str() {
+        "none" => Some(Symmetry::None),
+        "rotational180" => Some(Symmetry::Rotational180),
+        "rotational90" => Some(Symmetry::Rotational90),
+        "horizontal" => Some(Symmetry::Horizontal),
+        "vertical" => Some(Symmetry::Vertical),
+        "diagonal" => Some(Symmetry::Diagonal),
+        "antidiagonal" => Some(Symmetry::AntiDiagonal),
+        "full" => Some(Symmetry::Full),
+        _ => None,
+    }
+}
+
+/// Parses number of clues, e.g. `30`, or range of clues, e.g. `24-28`.
+fn parse_clues(text: &str) -> Option<RangeInclusive<usize>> {
+    let (min, max) = match text.find('-') {
+        Some(pos) => (&text[..pos], &text[pos + 1..]),
+        None => (text, text),
+    };
+    let (min, max) = (min.parse().ok()?, max.parse().ok()?);
+    if min <= max && max <= 81 {
+        Some(min..=max)
+    } else {
+        None
+    }
+}
+
+/// Writes puzzles to stdout in the format chosen on the command line.
+struct Output {
+    out: io::Stdout,
+    format: Format,
+    written: usize,
+}
+
+impl Output {
+    fn new(format: Format) -> Output {
+        Output {
+            out: io::stdout(),
+            format,
+            written: 0,
+        }
+    }
+
+    fn write(
+        &mut self,
+        board: &SudokuBoard,
+        comment: Option<String>,
+        metadata: &Metadata,
+    ) -> io::Result<()> {
+        let mut out = self.out.lock();
+        match self.format {
+            Format::Line => PuzzleWriter::new(&mut out).write_entry(&Entry {
+                board: board.clone(),
+                comment,
+            })?,
+            Format::Grid(format) => {
+                // Separate grids with empty lines, as read by GridReader
+                if self.written > 0 {
+                    writeln!(out)?;
+                }
+                grid::write(&mut out, board, metadata, format)?;
+            }
+        }
+        self.written += 1;
+        Ok(())
+    }
+}
+
+/// Puzzle read from an input, with a name for error messages.
+struct Input {
+    name: String,
+    board: SudokuBoard,
+    comment: Option<String>,
+    metadata: Metadata,
+}
+
+/// Reads every puzzle of a file, or of stdin for `-`.
+///
+/// Calls `f` for each puzzle, and reports puzzles that cannot be read.
+/// Returns false if any puzzle could not be read.
+fn read_puzzles<F>(path: &str, format: Option<Format>, f: &mut F) -> io::Result<bool>
+where
+    F: FnMut(Input) -> io::Result<bool>,
+{
+    let reader: Box<dyn BufRead> = if path == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        Box::new(BufReader::new(File::open(path).map_err(|e| {
+            io::Error::new(e.kind(), format!("{}: {}", path, e))
+        })?))
+    };
+    let name = if path == "-" { "<stdin>" } else { path };
+    let format = format.unwrap_or_else(|| Format::of_path(path));
+
+    let mut ok = true;
+    match format {
+        Format::Line => {
+            for entry in PuzzleReader::new(reader) {
+                match entry {
+                    Ok(entry) => {
+                        ok &= f(Input {
+                            name: String::from(name),
+                            board: entry.board,
+                            comment: entry.comment,
+                            metadata: Metadata::default(),
+                        })?
+                    }
+                    Err(e) => {
+                        eprintln!("{}: {}", name, e);
+                        ok = false;
+                    }
+                }
+            }
+        }
+        Format::Grid(_) => {
+            for grid in GridReader::new(reader) {
+                match grid {
+                    Ok((board, metadata)) => {
+                        ok &= f(Input {
+                            name: String::from(name),
+                            board,
+                            comment: None,
+                            metadata,
+                        })?
+                    }
+                    Err(e) => {
+                        eprintln!("{}: {}", name, e);
+                        ok = false;
+                    }
+                }
+            }
+        }
+    }
+    Ok(ok)
+}
+
+fn generate(args: &Args) -> Result<bool, Box<dyn std::error::Error>> {
+    let options = args.clone();
+    let puzzles = generate_batch(args.count as usize, args.threads, move |attempt| {
+        options.generator(attempt)
+    });
+
+    let mut output = Output::new(args.format);
+    let mut failures = 0;
+    for puzzle in puzzles {
+        let puzzle = match puzzle {
+            Ok(puzzle) => puzzle,
+            // Generator gave up on this attempt, try the next one
+            Err(e @ sudokugen::Error::GenerationFailed { .. }) => {
+                failures += 1;
+                if failures >= MAX_FAILURES {
+                    return Err(format!("{} ({} attempts in a row failed)", e, failures).into());
+                }
+                eprintln!("{}, retrying", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        failures = 0;
+        let comment = puzzle.metadata.rating.clone();
+        output.write(&puzzle.board, comment, &puzzle.metadata)?;
+    }
+    Ok(true)
+}
+
+/// Describes result of `Solver::verify` in a single line.
+fn describe(verification: &Verification) -> String {
+    match verification {
+        Verification::ValidWithBranches(branches) => format!("valid, {} branches", branches),
+        Verification::GuessingRequired => String::from("valid, requires guessing"),
+        Verification::InvalidClues(pairs) => {
+            let cells: Vec<String> = pairs
+                .iter()
+                .map(|&((r1, c1, val), (r2, c2, _))| {
+                    format!("{} at {} and {}", val, cell_name(r1, c1), cell_name(r2, c2))
+                })
+                .collect();
+            format!("invalid clues: {}", cells.join(", "))
+        }
+        Verification::Unsolvable(contradiction) => match contradiction {
+            Contradiction::NoCandidates { row, col } => {
+                format!("unsolvable: no value fits {}", cell_name(*row, *col))
+            }
+            Contradiction::NoPlacement { unit, value } => {
+                format!("unsolvable: {} cannot be placed in {}", value, unit)
+            }
+            Contradiction::NoBranchLeft => String::from("unsolvable"),
+        },
+        Verification::MultipleSolutions { differences, .. } => {
+            let cells: Vec<String> = differences
+                .iter()
+                .map(|&(row, col)| cell_name(row, col))
+                .collect();
+            format!("multiple solutions, differing at {}", cells.join(" "))
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<bool, Box<dyn std::error::Error>> {
+    if args.command == Command::Generate {
+        return generate(args);
+    }
+
+    let mut output = Output::new(args.format);
+    let mut solver = BitboardSolver::new();
+    let mut rater = Rater::new();
+    let stdout = io::stdout();
+
+    let mut handle = |input: Input| -> io::Result<bool> {
+        match args.command {
+            Command::Solve => match solver.solve(&input.board) {
+                Ok(solution) => {
+                    output.write(&solution.board, input.comment, &input.metadata)?;
+                }
+                Err(e) => {
+                    eprintln!("{}: {}: {}", input.name, input.board.to_line(), e);
+                    return Ok(false);
+                }
+            },
+            Command::Verify => {
+                let verification = solver.verify(&input.board);
+                writeln!(
+                    stdout.lock(),
+                    "{} {}",
+                    input.board.to_line(),
+                    describe(&verification)
+                )?;
+                return Ok(verification.is_valid());
+            }
+            Command::Rate => match rater.rate(&input.board) {
+                Ok(rating) => writeln!(
+                    stdout.lock(),
+                    "{} {:.1} {} {}",
+                    input.board.to_line(),
+                    rating.score,
+                    rating.difficulty,
+                    rating.hardest.unwrap_or("-")
+                )?,
+                Err(e) => {
+                    eprintln!("{}: {}: {}", input.name, input.board.to_line(), e);
+                    return Ok(false);
+                }
+            },
+            Command::Convert => output.write(&input.board, input.comment, &input.metadata)?,
+            Command::Generate => unreachable!(),
+        }
+        Ok(true)
+    };
+
+    let stdin = [String::from("-")];
+    let files = if args.files.is_empty() {
+        &stdin[..]
+    } else {
+        &args.files[..]
+    };
+    let mut ok = true;
+    for path in files {
+        ok &= read_puzzles(path, args.input, &mut handle)?;
+    }
+    Ok(ok)
+}
+
 fn main() {
-    env::set_var("RUST_LOG", "debug");
     env_logger::init();
 
-    if let Err(e) = generate_puzzle() {
-        error!("Error during puzzle generation: {}", e);
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    let args = match Args::parse(args.into_iter()) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
     }
 }
@@ -1,14 +1,118 @@
+use super::error::Error;
 use super::group::{new_array_from, Group};
+#[cfg(feature = "serde")]
+use serde::de::{self, Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::convert::TryFrom;
+use std::error;
 use std::fmt;
+use std::str::FromStr;
 
 pub type Placement = (usize, usize, u8);
 
 static VALUES: Group = [1, 2, 3, 4, 5, 6, 7, 8, 9];
 
-#[derive(Clone)]
+/// Row, column or box of the board, each numbered 0-8.
+///
+/// Boxes are numbered left to right, top to bottom.
+/// Displayed 1-based for humans, e.g. `row 3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    Row(usize),
+    Col(usize),
+    Box(usize),
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unit::Row(row) => write!(f, "row {}", row + 1),
+            Unit::Col(col) => write!(f, "column {}", col + 1),
+            Unit::Box(the_box) => write!(f, "box {}", the_box + 1),
+        }
+    }
+}
+
+impl Unit {
+    /// Returns all 27 units: rows, then columns, then boxes.
+    pub fn all() -> impl Iterator<Item = Unit> {
+        (0..9)
+            .map(Unit::Row)
+            .chain((0..9).map(Unit::Col))
+            .chain((0..9).map(Unit::Box))
+    }
+
+    /// Returns board indices (`row * 9 + col`) of the cells in unit.
+    pub fn cells(self) -> [usize; 9] {
+        let mut result = [0; 9];
+        for (pos, index) in result.iter_mut().enumerate() {
+            *index = match self {
+                Unit::Row(row) => row * 9 + pos,
+                Unit::Col(col) => pos * 9 + col,
+                Unit::Box(the_box) => box_to_index(the_box, pos),
+            };
+        }
+        result
+    }
+}
+
+/// Two cells with the same value in a row, column or box.
+///
+/// Cells are given as `(row, col)`, in board order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conflict {
+    pub unit: Unit,
+    pub value: u8,
+    pub cells: ((usize, usize), (usize, usize)),
+}
+
+impl Conflict {
+    fn new(unit: Unit, value: u8, a: usize, b: usize) -> Conflict {
+        let (first, second) = if a < b { (a, b) } else { (b, a) };
+        Conflict {
+            unit,
+            value,
+            cells: ((first / 9, first % 9), (second / 9, second % 9)),
+        }
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ((row_a, col_a), (row_b, col_b)) = self.cells;
+        write!(
+            f,
+            "Value {} at {} and {} in {}",
+            self.value,
+            cell_name(row_a, col_a),
+            cell_name(row_b, col_b),
+            self.unit
+        )
+    }
+}
+
+/// What `SudokuBoard::place_checked` does with a placement
+/// that repeats a value in a row, column or box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictCheck {
+    /// Place value and return the conflicts it creates.
+    Report,
+    /// Leave board unchanged and fail with `Error::ConflictingPlacement`.
+    Refuse,
+}
+
+#[derive(Clone, PartialEq)]
 pub struct SudokuBoard {
     pub values: [u8; 81],
     pub clues: [bool; 81],
+    /// Pencil marks entered by the player, 1 for each value noted in a cell.
+    ///
+    /// Unlike candidates computed by solvers, marks are not updated by
+    /// `place`; use `place_and_prune` to place a value and update them.
+    pub marks: [Group; 81],
 }
 
 impl fmt::Debug for SudokuBoard {
@@ -59,11 +163,183 @@ impl fmt::Display for SudokuBoard {
     }
 }
 
+/// Error returned when parsing a `SudokuBoard` from the 81-character line format.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseBoardError {
+    /// Input did not contain exactly 81 cells. Holds number of cells found.
+    InvalidLength(usize),
+    /// Input contained a character that is not a digit, `.` or whitespace.
+    InvalidCharacter { character: char, position: usize },
+    /// Two clues with the same value share a row, column or box.
+    ConflictingClues(Placement, Placement),
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBoardError::InvalidLength(len) => {
+                write!(f, "Expected 81 cells, found {}", len)
+            }
+            ParseBoardError::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "Invalid character '{}' at position {}",
+                character, position
+            ),
+            ParseBoardError::ConflictingClues((r1, c1, val), (r2, c2, _)) => write!(
+                f,
+                "Conflicting clues {} at {} and {}",
+                val,
+                cell_name(*r1, *c1),
+                cell_name(*r2, *c2)
+            ),
+        }
+    }
+}
+
+impl error::Error for ParseBoardError {}
+
+impl FromStr for SudokuBoard {
+    type Err = ParseBoardError;
+
+    /// Parses board from the 81-character line format.
+    ///
+    /// Cells are read row by row, digits `1`-`9` are clues and
+    /// `0` or `.` are blank cells. Whitespace is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut clues = Vec::with_capacity(81);
+        let mut cells = 0;
+
+        for (position, character) in s.chars().enumerate() {
+            let val = match character {
+                c if c.is_whitespace() => continue,
+                '.' => 0,
+                c => match c.to_digit(10) {
+                    Some(digit) => digit as u8,
+                    None => {
+                        return Err(ParseBoardError::InvalidCharacter {
+                            character,
+                            position,
+                        })
+                    }
+                },
+            };
+
+            if val > 0 && cells < 81 {
+                clues.push((cells / 9, cells % 9, val));
+            }
+            cells += 1;
+        }
+
+        if cells != 81 {
+            return Err(ParseBoardError::InvalidLength(cells));
+        }
+
+        let board = SudokuBoard::with_clues(&clues);
+        match board.first_conflict() {
+            Some((a, b)) => Err(ParseBoardError::ConflictingClues(a, b)),
+            None => Ok(board),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for SudokuBoard {
+    type Error = ParseBoardError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Serializes board as two strings in the 81-character line format:
+/// `clues` holding only the clues, and `values` holding all values.
+/// Pencil marks, if any, follow as `marks`: 81 strings of the values
+/// noted in each cell.
+#[cfg(feature = "serde")]
+impl Serialize for SudokuBoard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut clues = self.clone();
+        for (val, &clue) in clues.values.iter_mut().zip(self.clues.iter()) {
+            if !clue {
+                *val = 0;
+            }
+        }
+
+        let has_marks = self.marks.iter().any(|marks| marks.contains(&1));
+        let mut state = serializer.serialize_struct("SudokuBoard", 2 + has_marks as usize)?;
+        state.serialize_field("clues", &clues.to_line())?;
+        state.serialize_field("values", &self.to_line())?;
+        if has_marks {
+            let marks: Vec<String> = (0..81)
+                .map(|index| {
+                    self.get_marks(index / 9, index % 9)
+                        .iter()
+                        .map(|val| val.to_string())
+                        .collect()
+                })
+                .collect();
+            state.serialize_field("marks", &marks)?;
+        }
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SudokuBoard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Repr {
+            clues: String,
+            values: String,
+            #[serde(default)]
+            marks: Vec<String>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let clues: SudokuBoard = repr.clues.parse().map_err(de::Error::custom)?;
+        let mut board = clues.clone();
+        let values: Vec<char> = repr.values.chars().filter(|c| !c.is_whitespace()).collect();
+        if values.len() != 81 {
+            return Err(de::Error::custom("Values must be given for 81 cells"));
+        }
+        for (index, &val) in values.iter().enumerate() {
+            let val = match val {
+                '.' | '0' => 0,
+                c => c
+                    .to_digit(10)
+                    .ok_or_else(|| de::Error::custom(format!("Invalid value '{}'", c)))?
+                    as u8,
+            };
+            if clues.clues[index] && clues.values[index] != val {
+                return Err(de::Error::custom("Values do not match clues"));
+            }
+            board.values[index] = val;
+        }
+
+        if !repr.marks.is_empty() && repr.marks.len() != 81 {
+            return Err(de::Error::custom("Marks must be given for 81 cells"));
+        }
+        for (index, marks) in repr.marks.iter().enumerate() {
+            for c in marks.chars() {
+                match c.to_digit(10) {
+                    Some(val) if val > 0 => board.marks[index][val as usize - 1] = 1,
+                    _ => return Err(de::Error::custom(format!("Invalid mark '{}'", c))),
+                }
+            }
+        }
+
+        Ok(board)
+    }
+}
+
 impl SudokuBoard {
     pub fn with_clues(clues: &[Placement]) -> SudokuBoard {
         let mut result = SudokuBoard {
             values: [0; 81],
             clues: [false; 81],
+            marks: [[0; 9]; 81],
         };
 
         clues.iter().for_each(|(row, col, val)| {
@@ -80,13 +356,74 @@ impl SudokuBoard {
 
     pub fn is_valid(&self) -> bool {
         (0..9).all(|group| {
-            VALUES.iter().all(|&n| match self.get_val_count(group, n) {
-                (row, col, a_box) if row > 1 || col > 1 || a_box > 1 => false,
-                _ => true,
+            VALUES.iter().all(|&n| {
+                let (row, col, a_box) = self.get_val_count(group, n);
+                row <= 1 && col <= 1 && a_box <= 1
             })
         })
     }
 
+    /// Returns board in the 81-character line format, with `.` for blank cells.
+    ///
+    /// The result can be parsed back with `str::parse::<SudokuBoard>()`.
+    pub fn to_line(&self) -> String {
+        self.values
+            .iter()
+            .map(|&val| match val {
+                0 => '.',
+                v => std::char::from_digit(u32::from(v), 10).unwrap(),
+            })
+            .collect()
+    }
+
+    /// Returns every pair of cells sharing a value within a row, column or box.
+    ///
+    /// Cells sharing both a row (or column) and a box are reported once
+    /// for each unit. Conflicts are ordered by rows, then columns, then boxes.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut result = Vec::new();
+        for unit in Unit::all() {
+            let cells = unit.cells();
+            for (pos, &a) in cells.iter().enumerate() {
+                let value = self.values[a];
+                if value == 0 {
+                    continue;
+                }
+                for &b in cells[pos + 1..]
+                    .iter()
+                    .filter(|&&b| self.values[b] == value)
+                {
+                    result.push(Conflict::new(unit, value, a, b));
+                }
+            }
+        }
+        result
+    }
+
+    /// Finds all pairs of cells with the same value on a row, column or box,
+    /// once per pair and in board order.
+    pub(crate) fn conflicting_pairs(&self) -> Vec<(Placement, Placement)> {
+        let mut pairs: Vec<(Placement, Placement)> = self
+            .conflicts()
+            .iter()
+            .map(|conflict| {
+                let ((row_a, col_a), (row_b, col_b)) = conflict.cells;
+                (
+                    (row_a, col_a, conflict.value),
+                    (row_b, col_b, conflict.value),
+                )
+            })
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Finds first pair of cells with the same value on a row, column or box.
+    fn first_conflict(&self) -> Option<(Placement, Placement)> {
+        self.conflicting_pairs().into_iter().next()
+    }
+
     fn get_val_count(&self, group: usize, val: u8) -> (i32, i32, i32) {
         (0..9).fold((0, 0, 0), |(row, col, a_box), pos| {
             (
@@ -133,28 +470,169 @@ impl SudokuBoard {
         new_array_from(result.into_iter())
     }
 
-    pub fn place(&mut self, (row, col, val): Placement) -> Result<(), String> {
+    /// Places value, or clears cell when value is 0, without checking
+    /// Sudoku rules. See `place_checked` for a placement that does.
+    pub fn place(&mut self, placement: Placement) -> Result<(), Error> {
+        self.check_placement(placement)?;
+
+        let (row, col, val) = placement;
+        self.values[row * 9 + col] = val;
+        Ok(())
+    }
+
+    /// Places value like `place`, checking for conflicts with its peers.
+    ///
+    /// Returns conflicts created by the placement, or refuses it,
+    /// depending on `check`.
+    pub fn place_checked(
+        &mut self,
+        placement: Placement,
+        check: ConflictCheck,
+    ) -> Result<Vec<Conflict>, Error> {
+        self.check_placement(placement)?;
+
+        let (row, col, val) = placement;
+        let index = row * 9 + col;
+        let mut conflicts = Vec::new();
+        if val > 0 {
+            for &unit in &[
+                Unit::Row(row),
+                Unit::Col(col),
+                Unit::Box((row / 3) * 3 + col / 3),
+            ] {
+                for &peer in unit.cells().iter() {
+                    if peer != index && self.values[peer] == val {
+                        conflicts.push(Conflict::new(unit, val, index, peer));
+                    }
+                }
+            }
+        }
+
+        if check == ConflictCheck::Refuse && !conflicts.is_empty() {
+            return Err(Error::ConflictingPlacement(conflicts));
+        }
+        self.values[index] = val;
+        Ok(conflicts)
+    }
+
+    /// Returns values marked in cell, in ascending order.
+    pub fn get_marks(&self, row: usize, col: usize) -> Vec<u8> {
+        (1..10)
+            .filter(|&val| self.has_mark(row, col, val))
+            .collect()
+    }
+
+    /// Returns true if value 1-9 is marked in cell.
+    ///
+    /// Values outside 1-9 are never marked.
+    pub fn has_mark(&self, row: usize, col: usize, val: u8) -> bool {
+        match val {
+            1..=9 => self.marks[row * 9 + col][(val - 1) as usize] == 1,
+            _ => false,
+        }
+    }
+
+    /// Adds value 1-9 to the marks of a cell, or removes it if marked.
+    pub fn toggle_mark(&mut self, (row, col, val): Placement) -> Result<(), Error> {
+        if val == 0 {
+            return Err(Error::ValueOutOfRange(val));
+        }
+        self.check_placement((row, col, val))?;
+
+        let mark = &mut self.marks[row * 9 + col][(val - 1) as usize];
+        *mark = 1 - *mark;
+        Ok(())
+    }
+
+    /// Removes all marks of a cell.
+    pub fn clear_marks(&mut self, row: usize, col: usize) {
+        self.marks[row * 9 + col] = [0; 9];
+    }
+
+    /// Removes marks of all cells.
+    pub fn clear_all_marks(&mut self) {
+        self.marks = [[0; 9]; 81];
+    }
+
+    /// Marks every empty cell with the values allowed there,
+    /// replacing any existing marks.
+    pub fn fill_marks(&mut self) {
+        for index in 0..81 {
+            self.marks[index] = self.get_allowed_vals(index / 9, index % 9);
+        }
+    }
+
+    /// Removes value from the marks of cells sharing a row, column or
+    /// box with the placement. Returns the `(row, col)` of cells changed.
+    ///
+    /// Values outside 1-9 and cells outside the board change nothing.
+    pub fn prune_marks(&mut self, (row, col, val): Placement) -> Vec<(usize, usize)> {
+        if val == 0 || val > 9 || row > 8 || col > 8 {
+            return Vec::new();
+        }
+
+        let index = row * 9 + col;
+        let peers = (0..81).filter(|&peer| peer != index && are_peers(index, peer));
+        let mut pruned = Vec::new();
+        for peer in peers {
+            let mark = &mut self.marks[peer][(val - 1) as usize];
+            if *mark == 1 {
+                *mark = 0;
+                pruned.push((peer / 9, peer % 9));
+            }
+        }
+        pruned
+    }
+
+    /// Places value like `place` and updates pencil marks: removes the
+    /// marks of the cell and the value from the marks of its peers.
+    /// Clearing a cell leaves marks unchanged.
+    ///
+    /// Returns the `(row, col)` of peers whose marks were pruned.
+    pub fn place_and_prune(&mut self, placement: Placement) -> Result<Vec<(usize, usize)>, Error> {
+        self.place(placement)?;
+
+        let (row, col, val) = placement;
+        if val == 0 {
+            return Ok(Vec::new());
+        }
+        self.clear_marks(row, col);
+        Ok(self.prune_marks(placement))
+    }
+
+    /// Checks that placement is on the board and does not change a clue.
+    pub(crate) fn check_placement(&self, (row, col, val): Placement) -> Result<(), Error> {
         if val > 9 {
-            return Err(format!("Value out of range: {}", val));
+            return Err(Error::ValueOutOfRange(val));
         }
 
         if col > 8 || row > 8 {
-            return Err(format!("Coordinate out of range: ({}, {})", row, col));
+            return Err(Error::CoordinateOutOfRange { row, col });
         }
 
         if self.clues[row * 9 + col] {
-            Err(format!("Cannot place on clue at ({}, {})", row, col))
+            Err(Error::CannotPlaceOnClue { row, col })
         } else {
-            self.values[row * 9 + col] = val;
             Ok(())
         }
     }
 }
 
+/// Returns name of cell in 1-based row/column notation, e.g. `r3c5`.
+pub fn cell_name(row: usize, col: usize) -> String {
+    format!("r{}c{}", row + 1, col + 1)
+}
+
 fn box_to_index(group: usize, pos: usize) -> usize {
     ((group / 3) * 3 + pos / 3) * 9 + (group % 3) * 3 + pos % 3
 }
 
+/// Returns true if cells at board indices `a` and `b` share a row, column or box.
+pub(crate) fn are_peers(a: usize, b: usize) -> bool {
+    let (row_a, col_a, row_b, col_b) = (a / 9, a % 9, b / 9, b % 9);
+    row_a == row_b || col_a == col_b || (row_a / 3 == row_b / 3 && col_a / 3 == col_b / 3)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -179,21 +657,21 @@ mod tests {
     #[test]
     fn when_board_is_empty_then_is_filled_returns_false() {
         let board = SudokuBoard::with_clues(&[]);
-        assert_eq!(board.is_filled(), false);
+        assert!(!board.is_filled());
     }
 
     #[test]
     fn when_board_not_full_then_is_filled_returns_false() {
         let clues = [(1, 1, 1), (2, 7, 9), (7, 7, 5)];
         let board = SudokuBoard::with_clues(&clues);
-        assert_eq!(board.is_filled(), false);
+        assert!(!board.is_filled());
     }
 
     #[test]
     fn when_board_is_full_then_is_filled_returns_true() {
         let mut board = SudokuBoard::with_clues(&[]);
         board.values = [1; 81];
-        assert_eq!(board.is_filled(), true);
+        assert!(board.is_filled());
     }
 
     #[test]
@@ -246,11 +724,276 @@ mod tests {
         check_is_valid(&[(3, 3, 1), (5, 5, 1)], false);
     }
 
-    fn check_is_valid(clues: &[Placement], expected: bool) -> () {
+    fn check_is_valid(clues: &[Placement], expected: bool) {
         let board = SudokuBoard::with_clues(clues);
         assert_eq!(board.is_valid(), expected);
     }
 
+    static LINE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn when_parsing_line_then_place_clues_correctly() {
+        let board: SudokuBoard = LINE.parse().expect("Expected success");
+        assert_eq!(board.values[0], 4);
+        assert_eq!(board.values[6], 8);
+        assert_eq!(board.values[80], 0);
+        assert!(board.clues[0]);
+        assert!(!board.clues[1]);
+    }
+
+    #[test]
+    fn when_parsing_line_with_zeros_and_whitespace_then_blanks_are_empty() {
+        let line = format!("1{}\n   {}", "0".repeat(39), "0".repeat(41));
+        let board = SudokuBoard::try_from(line.as_str()).expect("Expected success");
+        assert_eq!(board.values[0], 1);
+        assert_eq!(board.values.iter().filter(|&&val| val > 0).count(), 1);
+    }
+
+    #[test]
+    fn when_parsing_line_of_wrong_length_then_return_error() {
+        assert_eq!(
+            "123".parse::<SudokuBoard>().unwrap_err(),
+            ParseBoardError::InvalidLength(3)
+        );
+        assert_eq!(
+            format!("{}.", LINE).parse::<SudokuBoard>().unwrap_err(),
+            ParseBoardError::InvalidLength(82)
+        );
+    }
+
+    #[test]
+    fn when_parsing_line_with_bad_character_then_return_error() {
+        let line = format!("..x{}", &LINE[3..]);
+        assert_eq!(
+            line.parse::<SudokuBoard>().unwrap_err(),
+            ParseBoardError::InvalidCharacter {
+                character: 'x',
+                position: 2
+            }
+        );
+    }
+
+    #[test]
+    fn when_parsing_line_with_conflicting_clues_then_return_error() {
+        let line = format!("4...4{}", &LINE[5..]);
+        let error = line.parse::<SudokuBoard>().unwrap_err();
+        assert_eq!(
+            error,
+            ParseBoardError::ConflictingClues((0, 0, 4), (0, 4, 4))
+        );
+        assert_eq!(error.to_string(), "Conflicting clues 4 at r1c1 and r1c5");
+    }
+
+    #[test]
+    fn when_serializing_board_then_parsing_returns_same_board() {
+        let board: SudokuBoard = LINE.parse().unwrap();
+        assert_eq!(board.to_line(), LINE);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn when_serializing_board_with_serde_then_deserialize_returns_same_board() {
+        let mut board: SudokuBoard = LINE.parse().unwrap();
+        board.place((0, 1, 9)).unwrap();
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(
+            json,
+            format!("{{\"clues\":\"{}\",\"values\":\"49{}\"}}", LINE, &LINE[2..])
+        );
+
+        let result: SudokuBoard = serde_json::from_str(&json).unwrap();
+        assert_eq!(result.values[..], board.values[..]);
+        assert_eq!(result.clues[..], board.clues[..]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn when_serializing_board_with_marks_then_deserialize_returns_marks() {
+        let mut board: SudokuBoard = LINE.parse().unwrap();
+        board.toggle_mark((0, 1, 2)).unwrap();
+        board.toggle_mark((0, 1, 9)).unwrap();
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains("\"marks\":[\"\",\"29\",\"\""));
+
+        let result: SudokuBoard = serde_json::from_str(&json).unwrap();
+        assert_eq!(result, board);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn when_deserializing_values_that_differ_from_clues_then_return_error() {
+        let json = format!("{{\"clues\":\"{}\",\"values\":\"5{}\"}}", LINE, &LINE[1..]);
+        assert!(serde_json::from_str::<SudokuBoard>(&json).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn when_deserializing_truncated_values_then_return_error() {
+        let json = format!("{{\"clues\":\"{}\",\"values\":\"{}\"}}", LINE, &LINE[..80]);
+        assert!(serde_json::from_str::<SudokuBoard>(&json).is_err());
+    }
+
+    #[test]
+    fn when_placing_outside_board_then_return_coordinate_error() {
+        let mut board = SudokuBoard::with_clues(&[]);
+        match board.place((2, 9, 1)) {
+            Err(Error::CoordinateOutOfRange { row: 2, col: 9 }) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn when_placing_on_clue_then_return_clue_error() {
+        let mut board = SudokuBoard::with_clues(&[(4, 4, 1)]);
+        match board.place((4, 4, 2)) {
+            Err(Error::CannotPlaceOnClue { row: 4, col: 4 }) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn when_values_repeat_then_return_conflict_for_each_unit() {
+        let board = SudokuBoard::with_clues(&[(0, 0, 5), (1, 1, 5), (0, 8, 5), (4, 4, 3)]);
+
+        let conflicts = board.conflicts();
+        assert_eq!(
+            conflicts,
+            vec![
+                Conflict {
+                    unit: Unit::Row(0),
+                    value: 5,
+                    cells: ((0, 0), (0, 8)),
+                },
+                Conflict {
+                    unit: Unit::Box(0),
+                    value: 5,
+                    cells: ((0, 0), (1, 1)),
+                },
+            ]
+        );
+        assert_eq!(
+            conflicts[1].to_string(),
+            "Value 5 at r1c1 and r2c2 in box 1"
+        );
+    }
+
+    #[test]
+    fn when_cells_share_row_and_box_then_return_pair_once_in_board_order() {
+        let board = SudokuBoard::with_clues(&[(1, 0, 5), (1, 2, 5), (0, 4, 5)]);
+
+        assert_eq!(board.conflicts().len(), 2);
+        assert_eq!(board.conflicting_pairs(), vec![((1, 0, 5), (1, 2, 5))]);
+    }
+
+    #[test]
+    fn when_placing_checked_then_report_conflicts() {
+        let mut board = SudokuBoard::with_clues(&[(0, 0, 5), (4, 4, 5)]);
+
+        let conflicts = board
+            .place_checked((0, 4, 5), ConflictCheck::Report)
+            .unwrap();
+        assert_eq!(board.values[4], 5);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].unit, Unit::Row(0));
+        assert_eq!(conflicts[1].unit, Unit::Col(4));
+        assert_eq!(board.conflicts(), conflicts);
+
+        let conflicts = board
+            .place_checked((8, 8, 1), ConflictCheck::Report)
+            .unwrap();
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn when_placing_checked_then_refuse_conflicts() {
+        let mut board = SudokuBoard::with_clues(&[(0, 0, 5)]);
+
+        match board.place_checked((1, 2, 5), ConflictCheck::Refuse) {
+            Err(Error::ConflictingPlacement(conflicts)) => {
+                assert_eq!(conflicts[0].unit, Unit::Box(0));
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        assert_eq!(board.values[11], 0);
+        assert!(board
+            .place_checked((1, 2, 0), ConflictCheck::Refuse)
+            .is_ok());
+    }
+
+    #[test]
+    fn when_toggling_marks_then_add_and_remove_value() {
+        let mut board = SudokuBoard::with_clues(&[(4, 4, 1)]);
+
+        board.toggle_mark((0, 0, 3)).unwrap();
+        board.toggle_mark((0, 0, 7)).unwrap();
+        assert_eq!(board.get_marks(0, 0), vec![3, 7]);
+        board.toggle_mark((0, 0, 3)).unwrap();
+        assert_eq!(board.get_marks(0, 0), vec![7]);
+
+        assert!(board.toggle_mark((4, 4, 2)).is_err());
+        assert!(board.toggle_mark((0, 0, 0)).is_err());
+
+        board.clear_marks(0, 0);
+        assert!(!board.has_mark(0, 0, 7));
+    }
+
+    #[test]
+    fn when_filling_marks_then_mark_allowed_values_of_empty_cells() {
+        let mut board: SudokuBoard = LINE.parse().unwrap();
+
+        board.fill_marks();
+        assert_eq!(board.get_marks(0, 0), Vec::<u8>::new());
+        for index in (0..81).filter(|&index| board.values[index] == 0) {
+            let (row, col) = (index / 9, index % 9);
+            assert_eq!(board.marks[index], board.get_allowed_vals(row, col));
+        }
+
+        board.clear_all_marks();
+        assert!(board.marks.iter().all(|marks| *marks == [0; 9]));
+    }
+
+    #[test]
+    fn when_pruning_marks_then_remove_value_from_peers() {
+        let mut board = SudokuBoard::with_clues(&[]);
+        board.fill_marks();
+
+        let pruned = board.prune_marks((4, 4, 5));
+        assert_eq!(pruned.len(), 20);
+        assert!(!board.has_mark(4, 0, 5));
+        assert!(!board.has_mark(3, 3, 5));
+        assert!(board.has_mark(4, 4, 5));
+        assert!(board.has_mark(0, 0, 5));
+        assert!(board.prune_marks((4, 4, 10)).is_empty());
+    }
+
+    #[test]
+    fn when_placing_and_pruning_then_clear_cell_marks_and_prune_peers() {
+        let mut board = SudokuBoard::with_clues(&[(0, 0, 1)]);
+        board.fill_marks();
+
+        let pruned = board.place_and_prune((4, 4, 5)).unwrap();
+        assert_eq!(pruned.len(), 20);
+        assert_eq!(board.values[40], 5);
+        assert_eq!(board.get_marks(4, 4), Vec::<u8>::new());
+        assert!(!board.has_mark(4, 0, 5));
+        assert!(board.has_mark(0, 1, 5));
+
+        assert!(board.place_and_prune((4, 4, 0)).unwrap().is_empty());
+        assert!(board.place_and_prune((0, 0, 2)).is_err());
+    }
+
+    #[test]
+    fn when_value_is_outside_range_then_it_is_not_marked() {
+        let mut board = SudokuBoard::with_clues(&[]);
+        board.fill_marks();
+
+        assert!(!board.has_mark(0, 0, 0));
+        assert!(!board.has_mark(0, 0, 10));
+    }
+
     #[test]
     fn when_cell_has_value_then_no_available_placements() {
         let board = SudokuBoard::with_clues(&[(4, 4, 1)]);
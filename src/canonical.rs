@@ -0,0 +1,225 @@
+//! Canonical form of Sudoku puzzles, for finding equivalent puzzles.
+//!
+//! Two puzzles are equivalent if one can be turned into the other by
+//! relabeling digits, permuting rows within a band, columns within a stack,
+//! bands and stacks, and transposing. Equivalent puzzles have the same
+//! canonical form: the equivalent puzzle that is smallest in line format,
+//! with blank cells ordered before digits (known as "minlex" form).
+
+use super::board::{Placement, SudokuBoard};
+
+/// Permutations of three rows, columns, bands or stacks.
+static PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// Returns canonical form of `SudokuBoard`, with every value as a clue.
+pub fn canonical_form(board: &SudokuBoard) -> SudokuBoard {
+    let values = canonical_values(&board.values);
+    let placements: Vec<Placement> = (0..81)
+        .filter(|&index| values[index] > 0)
+        .map(|index| (index / 9, index % 9, values[index]))
+        .collect();
+    SudokuBoard::with_clues(&placements)
+}
+
+/// Returns true if boards are equivalent, see module documentation.
+pub fn is_equivalent(a: &SudokuBoard, b: &SudokuBoard) -> bool {
+    canonical_values(&a.values)[..] == canonical_values(&b.values)[..]
+}
+
+/// Returns values of canonical form of board values.
+pub(crate) fn canonical_values(values: &[u8; 81]) -> [u8; 81] {
+    let mut transposed = [0; 81];
+    for (index, value) in transposed.iter_mut().enumerate() {
+        *value = values[(index % 9) * 9 + index / 9];
+    }
+
+    let mut best = [u8::MAX; 81];
+    for grid in &[*values, transposed] {
+        for stacks in &PERMUTATIONS {
+            for first in &PERMUTATIONS {
+                for second in &PERMUTATIONS {
+                    for third in &PERMUTATIONS {
+                        let within = [first, second, third];
+                        let mut cols = [0; 9];
+                        for (col, source) in cols.iter_mut().enumerate() {
+                            *source = stacks[col / 3] * 3 + within[col / 3][col % 3];
+                        }
+                        minimize_rows(grid, &cols, &mut best);
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Row order and digit labels chosen for the rows placed so far.
+#[derive(Clone, PartialEq)]
+struct State {
+    /// Bit per row of the grid already placed.
+    used: u16,
+    /// Band of the last placed row.
+    band: usize,
+    /// New label of each digit, or 0 if not seen yet.
+    labels: [u8; 10],
+    next_label: u8,
+}
+
+/// Finds smallest grid with columns in order `cols`, over every row order
+/// and digit labeling, and stores it in `best` if smaller.
+///
+/// Rows are placed one at a time, keeping every row order that gives
+/// the smallest rows so far, and giving up once the rows are larger
+/// than those of `best`.
+fn minimize_rows(grid: &[u8; 81], cols: &[usize; 9], best: &mut [u8; 81]) {
+    let mut states = vec![State {
+        used: 0,
+        band: 0,
+        labels: [0; 10],
+        next_label: 1,
+    }];
+    let mut result = [0; 81];
+    let mut smaller = false;
+
+    for pos in 0..9 {
+        let mut best_row: Option<[u8; 9]> = None;
+        let mut next_states: Vec<State> = Vec::new();
+
+        for state in &states {
+            for row in candidate_rows(state, pos) {
+                let mut next = state.clone();
+                next.used |= 1 << row;
+                next.band = row / 3;
+
+                let mut values = [0; 9];
+                for (value, &col) in values.iter_mut().zip(cols.iter()) {
+                    let digit = grid[row * 9 + col] as usize;
+                    if digit > 0 {
+                        if next.labels[digit] == 0 {
+                            next.labels[digit] = next.next_label;
+                            next.next_label += 1;
+                        }
+                        *value = next.labels[digit];
+                    }
+                }
+
+                match best_row.map(|best_row| values.cmp(&best_row)) {
+                    Some(std::cmp::Ordering::Greater) => continue,
+                    Some(std::cmp::Ordering::Equal) => (),
+                    _ => {
+                        best_row = Some(values);
+                        next_states.clear();
+                    }
+                }
+                if !next_states.contains(&next) {
+                    next_states.push(next);
+                }
+            }
+        }
+
+        let row = best_row.expect("Every row order has a next row");
+        if !smaller {
+            match row[..].cmp(&best[pos * 9..pos * 9 + 9]) {
+                std::cmp::Ordering::Greater => return,
+                std::cmp::Ordering::Less => smaller = true,
+                std::cmp::Ordering::Equal => (),
+            }
+        }
+        result[pos * 9..pos * 9 + 9].copy_from_slice(&row);
+        states = next_states;
+    }
+
+    if smaller {
+        *best = result;
+    }
+}
+
+/// Returns rows that can be placed at position `pos`, keeping
+/// rows of a band together.
+fn candidate_rows(state: &State, pos: usize) -> Vec<usize> {
+    match pos {
+        // First row of a band may come from any band not placed yet
+        0 | 3 | 6 => (0..3)
+            .filter(|band| state.used & (0b111 << (band * 3)) == 0)
+            .flat_map(|band| band * 3..band * 3 + 3)
+            .collect(),
+        _ => (state.band * 3..state.band * 3 + 3)
+            .filter(|row| state.used & (1 << row) == 0)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    static PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    /// Relabels digits, swaps first two rows, swaps last two stacks
+    /// and transposes.
+    fn transform(board: &SudokuBoard) -> SudokuBoard {
+        let mut values = [0; 81];
+        for row in 0..9 {
+            for col in 0..9 {
+                let source_row = match row {
+                    0 => 1,
+                    1 => 0,
+                    _ => row,
+                };
+                let source_col = match col / 3 {
+                    1 => col + 3,
+                    2 => col - 3,
+                    _ => col,
+                };
+                let value = board.values[source_row * 9 + source_col];
+                values[col * 9 + row] = if value > 0 { value % 9 + 1 } else { 0 };
+            }
+        }
+        let placements: Vec<Placement> = (0..81)
+            .filter(|&index| values[index] > 0)
+            .map(|index| (index / 9, index % 9, values[index]))
+            .collect();
+        SudokuBoard::with_clues(&placements)
+    }
+
+    #[test]
+    fn when_boards_are_equivalent_then_canonical_forms_are_equal() {
+        let board: SudokuBoard = PUZZLE.parse().unwrap();
+        let other = transform(&board);
+
+        assert_ne!(board.values[..], other.values[..]);
+        assert!(is_equivalent(&board, &other));
+        assert_eq!(canonical_form(&board), canonical_form(&other));
+    }
+
+    #[test]
+    fn when_boards_differ_then_canonical_forms_differ() {
+        let board: SudokuBoard = PUZZLE.parse().unwrap();
+        let mut other = board.clone();
+        other.place((0, 1, 1)).unwrap();
+
+        assert!(!is_equivalent(&board, &other));
+    }
+
+    #[test]
+    fn canonical_form_starts_with_blanks_and_labels_digits_in_order() {
+        let board: SudokuBoard = PUZZLE.parse().unwrap();
+        let canonical = canonical_form(&board);
+
+        let clues = |board: &SudokuBoard| board.values.iter().filter(|&&v| v > 0).count();
+        assert_eq!(clues(&canonical), clues(&board));
+        assert_eq!(canonical.values[0], 0);
+        let first = canonical.values.iter().find(|&&v| v > 0);
+        assert_eq!(first, Some(&1));
+        assert_eq!(canonical_form(&canonical), canonical);
+    }
+}
@@ -0,0 +1,114 @@
+use super::board::{Conflict, ParseBoardError};
+use super::generator::Difficulty;
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Error type for board, solver, generator and file operations.
+#[derive(Debug)]
+pub enum Error {
+    /// Value to place is not in range 0-9.
+    ValueOutOfRange(u8),
+    /// Row or column is not in range 0-8.
+    CoordinateOutOfRange { row: usize, col: usize },
+    /// Attempted to change the value of a clue.
+    CannotPlaceOnClue { row: usize, col: usize },
+    /// Placement refused because it repeats a value in a row, column or box.
+    ConflictingPlacement(Vec<Conflict>),
+    /// Board breaks Sudoku rules and cannot be solved.
+    InvalidBoard,
+    /// Board has no solution.
+    NoSolution,
+    /// Solver cannot continue without guessing.
+    GuessingRequired,
+    /// Solver gave up after specified number of iterations.
+    IterationLimit(u32),
+    /// Generator could not create puzzle of target difficulty.
+    GenerationFailed {
+        difficulty: Difficulty,
+        attempts: u32,
+    },
+    /// Generator could not fill clue pattern with a unique puzzle.
+    PatternFailed { clues: usize, attempts: u32 },
+    /// Generator panicked during specified attempt of a batch.
+    GeneratorPanicked(u32),
+    /// Generator options cannot be combined, with the reason.
+    InvalidOptions(String),
+    /// Board could not be parsed.
+    Parse(ParseBoardError),
+    /// Puzzle on specified line (starting at 1) of a file could not be parsed.
+    ParseLine { line: usize, error: ParseBoardError },
+    /// Saved game could not be loaded, with the reason.
+    InvalidSave(String),
+    /// Reading or writing a file failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ValueOutOfRange(val) => write!(f, "Value out of range: {}", val),
+            Error::CoordinateOutOfRange { row, col } => {
+                write!(f, "Coordinate out of range: ({}, {})", row, col)
+            }
+            Error::CannotPlaceOnClue { row, col } => {
+                write!(f, "Cannot place on clue at ({}, {})", row, col)
+            }
+            Error::ConflictingPlacement(conflicts) => {
+                write!(f, "Placement conflicts with Sudoku rules")?;
+                for conflict in conflicts {
+                    write!(f, "; {}", conflict)?;
+                }
+                Ok(())
+            }
+            Error::InvalidBoard => write!(f, "Cannot solve invalid board"),
+            Error::NoSolution => write!(f, "No solution found"),
+            Error::GuessingRequired => write!(f, "Cannot solve without guessing"),
+            Error::IterationLimit(max) => write!(f, "Max placements attempted: {}", max),
+            Error::GenerationFailed {
+                difficulty,
+                attempts,
+            } => write!(
+                f,
+                "Could not generate puzzle of difficulty {} after {} attempts",
+                difficulty, attempts
+            ),
+            Error::PatternFailed { clues, attempts } => write!(
+                f,
+                "Could not fill pattern of {} clues with a unique puzzle after {} attempts",
+                clues, attempts
+            ),
+            Error::GeneratorPanicked(attempt) => {
+                write!(f, "Generator panicked in attempt {}", attempt)
+            }
+            Error::InvalidOptions(reason) => write!(f, "Invalid generator options: {}", reason),
+            Error::Parse(e) => write!(f, "{}", e),
+            Error::ParseLine { line, error } => write!(f, "Line {}: {}", line, error),
+            Error::InvalidSave(reason) => write!(f, "Invalid saved game: {}", reason),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Parse(e) => Some(e),
+            Error::ParseLine { error, .. } => Some(error),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseBoardError> for Error {
+    fn from(e: ParseBoardError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,304 @@
+//! Multi-line grid formats from SadMan Software (`.sdk`) and Simple Sudoku (`.ss`).
+//!
+//! A grid holds one puzzle as nine rows of nine cells, with `.` or `0`
+//! for blank cells. Rows may be separated into boxes with `|`, and bands
+//! with lines of `-` and `+`. Lines starting with `#` carry metadata, where
+//! the character after `#` identifies the field, e.g. `#AJohn Doe` for author.
+//!
+//! Files usually hold one grid. Several grids are separated by empty lines,
+//! each with the metadata lines before it, see `GridReader`.
+
+use super::super::board::SudokuBoard;
+use super::super::error::Error;
+use super::super::generator::Metadata;
+use std::io::{self, BufRead, Write};
+
+/// Layout used when writing a grid file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridFormat {
+    /// Plain rows of nine cells (`.sdk`).
+    Sdk,
+    /// Rows with `|` between boxes and `-` lines between bands (`.ss`).
+    Ss,
+}
+
+/// Reads puzzles with their metadata from a `.sdk` or `.ss` file.
+///
+/// A grid ends at the first empty line after its rows, or at the end of
+/// the file. Grids that cannot be parsed are returned as errors, and
+/// reading continues with the next one.
+pub struct GridReader<R> {
+    lines: io::Lines<R>,
+    line: usize,
+    failed: bool,
+}
+
+impl<R: BufRead> GridReader<R> {
+    pub fn new(reader: R) -> GridReader<R> {
+        GridReader {
+            lines: reader.lines(),
+            line: 0,
+            failed: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for GridReader<R> {
+    type Item = Result<(SudokuBoard, Metadata), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let mut metadata = Metadata::default();
+        let mut cells = String::with_capacity(81);
+        let mut first_line = 0;
+        loop {
+            let text = match self.lines.next() {
+                Some(Ok(text)) => text,
+                Some(Err(e)) => {
+                    // Reader may keep failing, e.g. on a broken pipe
+                    self.failed = true;
+                    return Some(Err(Error::Io(e)));
+                }
+                None => break,
+            };
+            self.line += 1;
+            let text = text.trim();
+
+            if text.is_empty() {
+                if cells.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            if let Some(field) = text.strip_prefix('#') {
+                let mut chars = field.chars();
+                if let Some(tag) = chars.next() {
+                    set_field(&mut metadata, tag, chars.as_str().trim());
+                }
+                continue;
+            }
+
+            let row: String = text
+                .chars()
+                .filter(|c| !c.is_whitespace() && !"|-+".contains(*c))
+                .collect();
+            if !row.is_empty() {
+                if first_line == 0 {
+                    first_line = self.line;
+                }
+                cells.push_str(&row);
+            }
+        }
+
+        if cells.is_empty() {
+            return None;
+        }
+        Some(parse_grid(&cells, first_line).map(|board| (board, metadata)))
+    }
+}
+
+/// Reads the first puzzle with its metadata from a `.sdk` or `.ss` file.
+///
+/// Use `GridReader` for files with several puzzles.
+pub fn read<R: BufRead>(reader: R) -> Result<(SudokuBoard, Metadata), Error> {
+    match GridReader::new(reader).next() {
+        Some(result) => result,
+        None => Err(parse_grid("", 0).unwrap_err()),
+    }
+}
+
+fn parse_grid(cells: &str, line: usize) -> Result<SudokuBoard, Error> {
+    cells
+        .parse()
+        .map_err(|error| Error::ParseLine { line, error })
+}
+
+/// Writes a puzzle with its metadata in the specified `GridFormat`.
+pub fn write<W: Write>(
+    mut writer: W,
+    board: &SudokuBoard,
+    metadata: &Metadata,
+    format: GridFormat,
+) -> io::Result<()> {
+    write_metadata(&mut writer, metadata)?;
+
+    let line = board.to_line();
+    for row in 0..9 {
+        let cells = &line[row * 9..row * 9 + 9];
+        match format {
+            GridFormat::Sdk => writeln!(writer, "{}", cells)?,
+            GridFormat::Ss => {
+                writeln!(writer, "{}|{}|{}", &cells[0..3], &cells[3..6], &cells[6..9])?;
+                if row == 2 || row == 5 {
+                    writeln!(writer, "-----------")?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes metadata as `#` lines, one per field.
+pub(crate) fn write_metadata<W: Write>(writer: &mut W, metadata: &Metadata) -> io::Result<()> {
+    let fields = [
+        ('A', &metadata.author),
+        ('D', &metadata.title),
+        ('B', &metadata.date),
+        ('S', &metadata.source),
+        ('U', &metadata.rating),
+    ];
+    for (tag, value) in fields.iter() {
+        if let Some(value) = value {
+            writeln!(writer, "#{}{}", tag, value)?;
+        }
+    }
+    for comment in &metadata.comments {
+        writeln!(writer, "#C{}", comment)?;
+    }
+    for (tag, value) in &metadata.other {
+        writeln!(writer, "#{}{}", tag, value)?;
+    }
+    Ok(())
+}
+
+/// Sets metadata field identified by the character after `#`.
+pub(crate) fn set_field(metadata: &mut Metadata, tag: char, value: &str) {
+    let value = String::from(value);
+    match tag {
+        'A' => metadata.author = Some(value),
+        'D' => metadata.title = Some(value),
+        'B' => metadata.date = Some(value),
+        'S' => metadata.source = Some(value),
+        'U' => metadata.rating = Some(value),
+        'C' => metadata.comments.push(value),
+        _ => metadata.other.push((tag, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    static SDK: &str = "#AJane Doe
+#DMorning puzzle
+#CFirst comment
+#CSecond comment
+#UHard
+#Lhttp://example.com
+4.....8.5
+.3.......
+...7.....
+.2.....6.
+....8.4..
+....1....
+...6.3.7.
+5..2.....
+1.4......
+";
+
+    static SS: &str = "4..|...|8.5
+.3.|...|...
+...|7..|...
+-----------
+.2.|...|.6.
+...|.8.|4..
+...|.1.|...
+-----------
+...|6.3|.7.
+5..|2..|...
+1.4|...|...
+";
+
+    static LINE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn when_reading_sdk_then_return_board_and_metadata() {
+        let (board, metadata) = read(SDK.as_bytes()).unwrap();
+        assert_eq!(board.to_line(), LINE);
+        assert_eq!(metadata.author, Some(String::from("Jane Doe")));
+        assert_eq!(metadata.title, Some(String::from("Morning puzzle")));
+        assert_eq!(metadata.rating, Some(String::from("Hard")));
+        assert_eq!(metadata.comments.len(), 2);
+        assert_eq!(
+            metadata.other,
+            vec![('L', String::from("http://example.com"))]
+        );
+    }
+
+    #[test]
+    fn when_reading_ss_with_separators_then_return_board() {
+        let (board, metadata) = read(SS.as_bytes()).unwrap();
+        assert_eq!(board.to_line(), LINE);
+        assert_eq!(metadata, Metadata::default());
+    }
+
+    #[test]
+    fn when_grid_is_incomplete_then_return_error() {
+        let result = read(&SS.as_bytes()[..40]);
+        match result {
+            Err(Error::ParseLine { line, .. }) => assert_eq!(line, 1),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn when_writing_sdk_then_output_matches_input() {
+        let (board, metadata) = read(SDK.as_bytes()).unwrap();
+        let mut output = Vec::new();
+        write(&mut output, &board, &metadata, GridFormat::Sdk).unwrap();
+        let (_, written) = read(&output[..]).unwrap();
+        assert_eq!(written, metadata);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("5..2.....\n1.4......\n"));
+    }
+
+    #[test]
+    fn when_reading_several_grids_then_return_each_with_its_metadata() {
+        let mut output = Vec::new();
+        for (index, text) in [SDK, SS].iter().enumerate() {
+            let (board, metadata) = read(text.as_bytes()).unwrap();
+            if index > 0 {
+                writeln!(output).unwrap();
+            }
+            write(&mut output, &board, &metadata, GridFormat::Ss).unwrap();
+        }
+
+        let grids: Vec<(SudokuBoard, Metadata)> =
+            GridReader::new(&output[..]).map(Result::unwrap).collect();
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[0].0.to_line(), LINE);
+        assert_eq!(grids[0].1, read(SDK.as_bytes()).unwrap().1);
+        assert_eq!(grids[1].0.to_line(), LINE);
+        assert_eq!(grids[1].1, Metadata::default());
+    }
+
+    #[test]
+    fn when_grid_of_several_is_invalid_then_return_error_and_continue() {
+        let text = format!("{}\n{}\n\n{}", SDK, &SS[..40], SS);
+
+        let results: Vec<Result<(SudokuBoard, Metadata), Error>> =
+            GridReader::new(text.as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        match results[1] {
+            Err(Error::ParseLine { line, .. }) => assert_eq!(line, 17),
+            ref other => panic!("Unexpected result: {:?}", other),
+        }
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn when_writing_ss_then_output_matches_input() {
+        let (board, metadata) = read(SS.as_bytes()).unwrap();
+        let mut output = Vec::new();
+        write(&mut output, &board, &metadata, GridFormat::Ss).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), SS);
+    }
+}
@@ -0,0 +1,208 @@
+//! Line-per-puzzle collections, e.g. `.sdm` files.
+//!
+//! Each line holds one puzzle in the 81-character line format,
+//! optionally followed by whitespace and a comment or rating column.
+//! Empty lines and lines starting with `#` are skipped.
+
+use super::super::board::SudokuBoard;
+use super::super::error::Error;
+use std::io::{self, BufRead, Write};
+
+/// Puzzle read from a collection, with any trailing columns.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub board: SudokuBoard,
+    pub comment: Option<String>,
+}
+
+/// Lazily reads puzzles from a line-per-puzzle collection.
+///
+/// Ends after the first io error, which is returned as `Error::Io`.
+pub struct PuzzleReader<R> {
+    lines: io::Lines<R>,
+    line: usize,
+    failed: bool,
+}
+
+impl<R: BufRead> PuzzleReader<R> {
+    pub fn new(reader: R) -> PuzzleReader<R> {
+        PuzzleReader {
+            lines: reader.lines(),
+            line: 0,
+            failed: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PuzzleReader<R> {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        loop {
+            let text = match self.lines.next()? {
+                Ok(text) => text,
+                Err(e) => {
+                    // Reader may keep failing, e.g. on a broken pipe
+                    self.failed = true;
+                    return Some(Err(Error::Io(e)));
+                }
+            };
+            self.line += 1;
+
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            return Some(parse_entry(text, self.line));
+        }
+    }
+}
+
+fn parse_entry(text: &str, line: usize) -> Result<Entry, Error> {
+    let (puzzle, rest) = match text.find(char::is_whitespace) {
+        Some(pos) => text.split_at(pos),
+        None => (text, ""),
+    };
+
+    let board = puzzle
+        .parse()
+        .map_err(|error| Error::ParseLine { line, error })?;
+    let comment = match rest.trim() {
+        "" => None,
+        comment => Some(String::from(comment)),
+    };
+
+    Ok(Entry { board, comment })
+}
+
+/// Writes puzzles to a line-per-puzzle collection.
+pub struct PuzzleWriter<W> {
+    writer: W,
+    blank: char,
+}
+
+impl<W: Write> PuzzleWriter<W> {
+    /// Creates new `PuzzleWriter` that writes blank cells as `.`.
+    ///
+    /// Can be further customized with builder method `blank`.
+    pub fn new(writer: W) -> PuzzleWriter<W> {
+        PuzzleWriter { writer, blank: '.' }
+    }
+
+    /// Sets character to write for blank cells, e.g. `0` for `.sdm` files.
+    pub fn blank(mut self, blank: char) -> PuzzleWriter<W> {
+        self.blank = blank;
+        self
+    }
+
+    /// Writes board as a single line.
+    pub fn write(&mut self, board: &SudokuBoard) -> io::Result<()> {
+        let line = board.to_line().replace('.', &self.blank.to_string());
+        writeln!(self.writer, "{}", line)
+    }
+
+    /// Writes board followed by its comment column, if any.
+    pub fn write_entry(&mut self, entry: &Entry) -> io::Result<()> {
+        let line = entry.board.to_line().replace('.', &self.blank.to_string());
+        match entry.comment {
+            Some(ref comment) => writeln!(self.writer, "{} {}", line, comment),
+            None => writeln!(self.writer, "{}", line),
+        }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    static FIRST: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+    static SECOND: &str =
+        "52...6.........7.13...........4..8..6......5...........418.........3..2...87.....";
+
+    #[test]
+    fn when_reading_collection_then_return_all_puzzles() {
+        let data = format!("{}\n{}\n", FIRST, SECOND);
+        let boards: Vec<SudokuBoard> = PuzzleReader::new(data.as_bytes())
+            .map(|entry| entry.unwrap().board)
+            .collect();
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].to_line(), FIRST);
+        assert_eq!(boards[1].to_line(), SECOND);
+    }
+
+    #[test]
+    fn when_line_has_trailing_columns_then_return_them_as_comment() {
+        let data = format!("{}\t9.0  # hardest\n{}\n", FIRST, SECOND);
+        let entries: Vec<Entry> = PuzzleReader::new(data.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(entries[0].comment, Some(String::from("9.0  # hardest")));
+        assert_eq!(entries[1].comment, None);
+    }
+
+    #[test]
+    fn when_line_is_empty_or_comment_then_skip_it() {
+        let data = format!("# collection\n\n{}\n\n", FIRST);
+        assert_eq!(PuzzleReader::new(data.as_bytes()).count(), 1);
+    }
+
+    #[test]
+    fn when_line_is_invalid_then_return_error_with_line_number() {
+        let data = format!("{}\n\n123\n{}\n", FIRST, SECOND);
+        let results: Vec<_> = PuzzleReader::new(data.as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        match results[1] {
+            Err(Error::ParseLine { line, .. }) => assert_eq!(line, 3),
+            ref other => panic!("Unexpected result: {:?}", other),
+        }
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn when_reading_fails_then_return_error_once_and_stop() {
+        struct Failing;
+
+        impl io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken"))
+            }
+        }
+
+        let mut reader = PuzzleReader::new(io::BufReader::new(Failing));
+        match reader.next() {
+            Some(Err(Error::Io(_))) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn when_writing_entries_then_reading_returns_same_entries() {
+        let entry = Entry {
+            board: FIRST.parse().unwrap(),
+            comment: Some(String::from("3.4")),
+        };
+        let mut writer = PuzzleWriter::new(Vec::new()).blank('0');
+        writer.write_entry(&entry).unwrap();
+        writer.write(&SECOND.parse().unwrap()).unwrap();
+        let data = writer.into_inner();
+
+        assert!(data.starts_with(b"400000805"));
+        let entries: Vec<Entry> = PuzzleReader::new(&data[..]).map(Result::unwrap).collect();
+        assert_eq!(entries[0].board.to_line(), FIRST);
+        assert_eq!(entries[0].comment, Some(String::from("3.4")));
+        assert_eq!(entries[1].board.to_line(), SECOND);
+    }
+}
@@ -0,0 +1,5 @@
+//! Reading and writing of Sudoku puzzle files.
+
+pub mod grid;
+pub mod line;
+pub mod save;
@@ -0,0 +1,399 @@
+//! Versioned text format for suspending and resuming a `GameSession`.
+//!
+//! Each line holds a field name, whitespace and the field value, e.g.
+//! `seed 42`. Cells are written as `r3c5`, rows and columns counted
+//! from 1. Lines starting with `#` carry puzzle metadata as in grid files,
+//! and empty lines are skipped.
+//!
+//! ```text
+//! version 1.1
+//! difficulty Hard
+//! seed 42
+//! #AJane Doe
+//! clues 4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......
+//! values 49....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......
+//! mark r1c3 26
+//! elapsed 83250
+//! paused true
+//! mistakes 1
+//! move mark r1c3 2
+//! move mark r1c3 6
+//! move place r1c2 9
+//! undone place r1c3 1
+//! ```
+//!
+//! `elapsed` is playing time in milliseconds, and `paused` tells whether
+//! the clock was stopped, `false` for files without it. `move` lines list the move
+//! history, oldest first, and `undone` lines the moves that can be redone,
+//! in the order they were undone. Loading replays the history, and fails
+//! if the result does not match `values` and `mark` lines.
+//!
+//! Versions are written as `major` or `major.minor`. Minor versions only
+//! add fields, and readers skip fields they do not know, so files of a
+//! newer minor version can be loaded with the new fields ignored. Files
+//! of another major version are rejected.
+
+use super::super::board::{cell_name, SudokuBoard};
+use super::super::error::Error;
+use super::super::game::{GameSession, Move};
+use super::super::generator::{Difficulty, Metadata, Puzzle};
+use super::grid::{set_field, write_metadata};
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+/// Major version of the format written by `write`.
+pub const VERSION: u32 = 1;
+
+/// Minor version of the format written by `write`.
+///
+/// Version 1.1 added `paused`.
+pub const MINOR_VERSION: u32 = 1;
+
+/// Reads a saved game, restoring its history, clock and mistakes.
+pub fn read<R: BufRead>(reader: R) -> Result<GameSession, Error> {
+    let mut version = None;
+    let mut difficulty = Difficulty::Easy;
+    let mut seed = None;
+    let mut metadata = Metadata::default();
+    let mut clues = None;
+    let mut values = None;
+    let mut marks = Vec::new();
+    let mut elapsed = Duration::from_millis(0);
+    let mut paused = false;
+    let mut mistakes = 0;
+    let mut moves = Vec::new();
+    let mut undone = Vec::new();
+
+    for (number, text) in reader.lines().enumerate() {
+        let text = text?;
+        let text = text.trim();
+        let line = number + 1;
+
+        if let Some(field) = text.strip_prefix('#') {
+            let mut chars = field.chars();
+            if let Some(tag) = chars.next() {
+                set_field(&mut metadata, tag, chars.as_str().trim());
+            }
+            continue;
+        }
+
+        let (name, value) = match text.find(char::is_whitespace) {
+            Some(pos) => (&text[..pos], text[pos..].trim()),
+            None => (text, ""),
+        };
+        let invalid = || Error::InvalidSave(format!("Line {}: invalid {} '{}'", line, name, value));
+
+        match name {
+            "" => (),
+            "version" => version = Some(parse_version(value).ok_or_else(invalid)?),
+            "difficulty" => difficulty = parse_difficulty(value).ok_or_else(invalid)?,
+            "seed" => seed = Some(value.parse().map_err(|_| invalid())?),
+            "clues" => clues = Some(parse_board(value, line)?),
+            "values" => values = Some(parse_board(value, line)?),
+            "mark" => marks.push(parse_marks(value).ok_or_else(invalid)?),
+            "elapsed" => elapsed = Duration::from_millis(value.parse().map_err(|_| invalid())?),
+            "paused" => paused = value.parse().map_err(|_| invalid())?,
+            "mistakes" => mistakes = value.parse().map_err(|_| invalid())?,
+            "move" => moves.push(parse_move(value).ok_or_else(invalid)?),
+            "undone" => undone.push(parse_move(value).ok_or_else(invalid)?),
+            // Field added by a later version
+            _ => (),
+        }
+    }
+
+    match version {
+        None => return Err(Error::InvalidSave(String::from("Missing version"))),
+        Some(major) if major != VERSION => {
+            return Err(Error::InvalidSave(format!(
+                "Unsupported version {}, expected {}",
+                major, VERSION
+            )))
+        }
+        Some(_) => (),
+    }
+    let board = clues.ok_or_else(|| Error::InvalidSave(String::from("Missing clues")))?;
+    let puzzle = Puzzle {
+        board,
+        difficulty,
+        metadata,
+        seed,
+    };
+    let session = GameSession::restore(puzzle, &moves, &undone, mistakes, elapsed, paused)?;
+
+    let board = session.board();
+    if let Some(values) = values {
+        if values.values[..] != board.values[..] {
+            return Err(Error::InvalidSave(String::from(
+                "Values do not match move history",
+            )));
+        }
+    }
+    let mut expected = [[0; 9]; 81];
+    for (index, group) in marks {
+        expected[index] = group;
+    }
+    if expected[..] != board.marks[..] {
+        return Err(Error::InvalidSave(String::from(
+            "Marks do not match move history",
+        )));
+    }
+
+    Ok(session)
+}
+
+/// Writes game in the current version of the format.
+pub fn write<W: Write>(mut writer: W, session: &GameSession) -> io::Result<()> {
+    let puzzle = session.puzzle();
+    let board = session.board();
+
+    writeln!(writer, "version {}.{}", VERSION, MINOR_VERSION)?;
+    writeln!(writer, "difficulty {}", puzzle.difficulty)?;
+    if let Some(seed) = puzzle.seed {
+        writeln!(writer, "seed {}", seed)?;
+    }
+    write_metadata(&mut writer, &puzzle.metadata)?;
+
+    let mut clues = puzzle.board.clone();
+    for (val, &clue) in clues.values.iter_mut().zip(puzzle.board.clues.iter()) {
+        if !clue {
+            *val = 0;
+        }
+    }
+    writeln!(writer, "clues {}", clues.to_line())?;
+    writeln!(writer, "values {}", board.to_line())?;
+    for index in 0..81 {
+        let marks = board.get_marks(index / 9, index % 9);
+        if !marks.is_empty() {
+            let values: String = marks.iter().map(|val| val.to_string()).collect();
+            writeln!(
+                writer,
+                "mark {} {}",
+                cell_name(index / 9, index % 9),
+                values
+            )?;
+        }
+    }
+
+    writeln!(writer, "elapsed {}", session.elapsed().as_millis())?;
+    writeln!(writer, "paused {}", session.is_paused())?;
+    writeln!(writer, "mistakes {}", session.mistakes())?;
+    for action in session.moves() {
+        writeln!(writer, "move {}", move_text(action))?;
+    }
+    for action in session.undone_moves() {
+        writeln!(writer, "undone {}", move_text(action))?;
+    }
+
+    Ok(())
+}
+
+fn move_text(action: Move) -> String {
+    match action {
+        Move::Place { row, col, value } => format!("place {} {}", cell_name(row, col), value),
+        Move::Erase { row, col } => format!("erase {}", cell_name(row, col)),
+        Move::ToggleCandidate { row, col, value } => {
+            format!("mark {} {}", cell_name(row, col), value)
+        }
+    }
+}
+
+fn parse_board(value: &str, line: usize) -> Result<SudokuBoard, Error> {
+    value
+        .parse()
+        .map_err(|error| Error::ParseLine { line, error })
+}
+
+fn parse_difficulty(value: &str) -> Option<Difficulty> {
+    match value {
+        "Easy" => Some(Difficulty::Easy),
+        "Medium" => Some(Difficulty::Medium),
+        "Hard" => Some(Difficulty::Hard),
+        "Evil" => Some(Difficulty::Evil),
+        _ => None,
+    }
+}
+
+/// Parses version in `major` or `major.minor` notation, returning major version.
+fn parse_version(text: &str) -> Option<u32> {
+    let mut parts = text.splitn(2, '.');
+    let major = parts.next()?.parse().ok()?;
+    match parts.next() {
+        Some(minor) => minor.parse::<u32>().ok().map(|_| major),
+        None => Some(major),
+    }
+}
+
+/// Parses cell in `r3c5` notation, returning `(row, col)`.
+fn parse_cell(text: &str) -> Option<(usize, usize)> {
+    let rest = text.strip_prefix('r')?;
+    let pos = rest.find('c')?;
+    let row: usize = rest[..pos].parse().ok()?;
+    let col: usize = rest[pos + 1..].parse().ok()?;
+    if (1..=9).contains(&row) && (1..=9).contains(&col) {
+        Some((row - 1, col - 1))
+    } else {
+        None
+    }
+}
+
+fn parse_value(text: &str) -> Option<u8> {
+    match text.parse() {
+        Ok(value) if (1..=9).contains(&value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Parses marks of a cell, e.g. `r1c3 26`, returning board index and marks.
+fn parse_marks(text: &str) -> Option<(usize, [u8; 9])> {
+    let mut parts = text.split_whitespace();
+    let (row, col) = parse_cell(parts.next()?)?;
+    let mut group = [0; 9];
+    for c in parts.next()?.chars() {
+        group[(parse_value(&c.to_string())? - 1) as usize] = 1;
+    }
+    Some((row * 9 + col, group))
+}
+
+fn parse_move(text: &str) -> Option<Move> {
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    match parts.as_slice() {
+        ["place", cell, value] => {
+            let (row, col) = parse_cell(cell)?;
+            let value = parse_value(value)?;
+            Some(Move::Place { row, col, value })
+        }
+        ["erase", cell] => {
+            let (row, col) = parse_cell(cell)?;
+            Some(Move::Erase { row, col })
+        }
+        ["mark", cell, value] => {
+            let (row, col) = parse_cell(cell)?;
+            let value = parse_value(value)?;
+            Some(Move::ToggleCandidate { row, col, value })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    static PUZZLE: &str =
+        "..3456789456789123789123456..4365897365897214897214365531642978642978531978531642";
+
+    fn session() -> GameSession {
+        let mut board: SudokuBoard = PUZZLE.parse().unwrap();
+        board.place((0, 0, 1)).unwrap();
+        board.clues[0] = true;
+
+        GameSession::new(Puzzle {
+            board,
+            difficulty: Difficulty::Hard,
+            metadata: Metadata {
+                author: Some(String::from("Jane Doe")),
+                ..Metadata::default()
+            },
+            seed: Some(42),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn when_reading_written_game_then_restore_session() {
+        let mut game = session();
+        game.toggle_candidate(3, 0, 1).unwrap();
+        game.toggle_candidate(3, 0, 2).unwrap();
+        game.place(0, 1, 4).unwrap();
+        game.place(0, 1, 2).unwrap();
+        game.place(3, 1, 1).unwrap();
+        game.undo();
+
+        let mut output = Vec::new();
+        write(&mut output, &game).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.starts_with("version 1.1\ndifficulty Hard\nseed 42\n#AJane Doe\n"));
+        assert!(text.contains("\nmark r4c1 12\n"));
+        assert!(text.contains("\npaused false\n"));
+        assert!(text.contains("\nmove place r1c2 4\n"));
+        assert!(text.ends_with("\nundone place r4c2 1\n"));
+
+        let restored = read(text.as_bytes()).unwrap();
+        assert_eq!(restored.board(), game.board());
+        assert_eq!(restored.moves(), game.moves());
+        assert_eq!(restored.undone_moves(), game.undone_moves());
+        assert_eq!(restored.mistakes(), 1);
+        assert!(!restored.is_paused());
+        assert!(restored.elapsed() >= Duration::from_millis(game.elapsed().as_millis() as u64));
+        assert_eq!(restored.puzzle().seed, Some(42));
+        assert_eq!(restored.puzzle().difficulty, Difficulty::Hard);
+        assert_eq!(restored.puzzle().metadata, game.puzzle().metadata);
+    }
+
+    #[test]
+    fn when_reading_paused_game_then_keep_it_paused() {
+        let mut game = session();
+        game.place(0, 1, 2).unwrap();
+        game.pause();
+
+        let mut output = Vec::new();
+        write(&mut output, &game).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("\npaused true\n"));
+
+        let restored = read(text.as_bytes()).unwrap();
+        assert!(restored.is_paused());
+        assert_eq!(restored.elapsed().as_millis(), game.elapsed().as_millis());
+
+        // Files of version 1.0 have no paused field
+        let text = format!("version 1\nclues 1.{}\n", &PUZZLE[2..]);
+        assert!(!read(text.as_bytes()).unwrap().is_paused());
+    }
+
+    #[test]
+    fn when_reading_unknown_fields_then_skip_them() {
+        let text = format!(
+            "version 1.2\nclues 1.{}\ntheme dark\nmove place r1c2 2\n",
+            &PUZZLE[2..]
+        );
+
+        let game = read(text.as_bytes()).unwrap();
+        assert_eq!(game.board().values[1], 2);
+        assert_eq!(game.puzzle().seed, None);
+    }
+
+    #[test]
+    fn when_major_version_is_unknown_then_return_error() {
+        let text = format!("version 2\nclues 1.{}\n", &PUZZLE[2..]);
+
+        match read(text.as_bytes()) {
+            Err(Error::InvalidSave(message)) => assert!(message.contains("version 2")),
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn when_values_do_not_match_history_then_return_error() {
+        let text = format!(
+            "version 1\nclues 1.{}\nvalues 12{}\n",
+            &PUZZLE[2..],
+            &PUZZLE[2..]
+        );
+
+        match read(text.as_bytes()) {
+            Err(Error::InvalidSave(_)) => (),
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn when_move_is_invalid_then_return_error_with_line_number() {
+        let text = format!("version 1\nclues 1.{}\nmove place r1c0 2\n", &PUZZLE[2..]);
+
+        match read(text.as_bytes()) {
+            Err(Error::InvalidSave(reason)) => assert!(reason.starts_with("Line 3:")),
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use super::board::SudokuBoard;
+use super::error::Error;
+use super::generator::Puzzle;
+use super::group::Group;
+use super::solver::bitboard::BitboardSolver;
+use super::solver::Solver;
+use std::time::{Duration, Instant};
+
+/// Move made by the player in a `GameSession`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    /// Place value 1-9 in cell.
+    Place { row: usize, col: usize, value: u8 },
+    /// Clear value of cell.
+    Erase { row: usize, col: usize },
+    /// Add or remove candidate value 1-9 from cell notes.
+    ToggleCandidate { row: usize, col: usize, value: u8 },
+}
+
+/// Move in history, with the cell value and marks it replaced
+/// and the cells whose marks it pruned.
+struct Command {
+    action: Move,
+    previous: u8,
+    marks: Group,
+    pruned: Vec<(usize, usize)>,
+}
+
+/// Game of Sudoku in progress.
+///
+/// # Remarks
+///
+/// Candidates toggled by the player are kept as pencil marks on the
+/// board. Placing a value removes the marks of its cell and the value
+/// from the marks of its peers.
+///
+/// Moves are kept as a command history with unlimited undo and redo;
+/// making a new move discards moves that were undone. Placing a value
+/// that differs from the solution counts as a mistake, even if the
+/// move is later undone.
+///
+/// The clock runs from creation until the puzzle is completed,
+/// except while the game is paused.
+pub struct GameSession {
+    puzzle: Puzzle,
+    board: SudokuBoard,
+    solution: SudokuBoard,
+    done: Vec<Command>,
+    undone: Vec<Command>,
+    mistakes: u32,
+    elapsed: Duration,
+    running_since: Option<Instant>,
+    paused: bool,
+}
+
+impl GameSession {
+    /// Starts new game of puzzle.
+    ///
+    /// Fails if the puzzle breaks Sudoku rules or has no solution.
+    pub fn new(puzzle: Puzzle) -> Result<GameSession, Error> {
+        let solution = BitboardSolver::new().solve(&puzzle.board)?.board;
+
+        let mut session = GameSession {
+            board: puzzle.board.clone(),
+            puzzle,
+            solution,
+            done: Vec::new(),
+            undone: Vec::new(),
+            mistakes: 0,
+            elapsed: Duration::from_secs(0),
+            running_since: None,
+            paused: false,
+        };
+        session.update_clock();
+        Ok(session)
+    }
+
+    /// Restores game of puzzle by replaying moves, then undoing the
+    /// undone moves, as returned by `moves` and `undone_moves`.
+    /// The clock only runs again if the game was not paused.
+    pub(crate) fn restore(
+        puzzle: Puzzle,
+        moves: &[Move],
+        undone: &[Move],
+        mistakes: u32,
+        elapsed: Duration,
+        paused: bool,
+    ) -> Result<GameSession, Error> {
+        let mut session = GameSession::new(puzzle)?;
+        // Keep the clock stopped while replaying
+        session.pause();
+        for &action in moves.iter().chain(undone.iter().rev()) {
+            session.make_move(action)?;
+        }
+        for _ in undone {
+            session.undo();
+        }
+
+        session.mistakes = mistakes;
+        session.elapsed = elapsed;
+        if !paused {
+            session.resume();
+        }
+        Ok(session)
+    }
+
+    /// Returns puzzle as it was when the game started.
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    /// Returns board with the values and marks entered by the player.
+    pub fn board(&self) -> &SudokuBoard {
+        &self.board
+    }
+
+    /// Places value 1-9 in cell.
+    pub fn place(&mut self, row: usize, col: usize, value: u8) -> Result<(), Error> {
+        if value == 0 {
+            return Err(Error::ValueOutOfRange(value));
+        }
+        self.make_move(Move::Place { row, col, value })
+    }
+
+    /// Clears value of cell.
+    pub fn erase(&mut self, row: usize, col: usize) -> Result<(), Error> {
+        self.make_move(Move::Erase { row, col })
+    }
+
+    /// Adds candidate value 1-9 to cell notes, or removes it if noted.
+    pub fn toggle_candidate(&mut self, row: usize, col: usize, value: u8) -> Result<(), Error> {
+        if value == 0 {
+            return Err(Error::ValueOutOfRange(value));
+        }
+        self.make_move(Move::ToggleCandidate { row, col, value })
+    }
+
+    /// Reverts last move. Returns the move, or `None` if there is
+    /// nothing to undo.
+    pub fn undo(&mut self) -> Option<Move> {
+        let command = self.done.pop()?;
+        match command.action {
+            Move::Place { row, col, value } => {
+                self.board.values[row * 9 + col] = command.previous;
+                self.board.marks[row * 9 + col] = command.marks;
+                for &(row, col) in &command.pruned {
+                    self.board.marks[row * 9 + col][(value - 1) as usize] = 1;
+                }
+            }
+            Move::Erase { row, col } => self.board.values[row * 9 + col] = command.previous,
+            Move::ToggleCandidate { row, col, value } => self.toggle(row, col, value),
+        }
+        let action = command.action;
+        self.undone.push(command);
+        self.update_clock();
+        Some(action)
+    }
+
+    /// Makes last undone move again. Returns the move, or `None`
+    /// if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Move> {
+        let mut command = self.undone.pop()?;
+        command.pruned = self.apply(command.action);
+        let action = command.action;
+        self.done.push(command);
+        self.update_clock();
+        Some(action)
+    }
+
+    /// Returns true if there is a move to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Returns true if there is an undone move to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Returns moves made so far, oldest first, excluding undone moves.
+    pub fn moves(&self) -> Vec<Move> {
+        self.done.iter().map(|command| command.action).collect()
+    }
+
+    /// Returns undone moves, in the order they were undone.
+    ///
+    /// The last move is the first to be redone.
+    pub fn undone_moves(&self) -> Vec<Move> {
+        self.undone.iter().map(|command| command.action).collect()
+    }
+
+    /// Returns number of values placed that differ from the solution.
+    pub fn mistakes(&self) -> u32 {
+        self.mistakes
+    }
+
+    /// Returns true if the board is filled without breaking Sudoku rules.
+    pub fn is_complete(&self) -> bool {
+        self.board.is_filled() && self.board.is_valid()
+    }
+
+    /// Returns time spent playing, excluding pauses.
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.elapsed + since.elapsed(),
+            None => self.elapsed,
+        }
+    }
+
+    /// Stops the clock until `resume` is called.
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.update_clock();
+    }
+
+    /// Restarts the clock after `pause`.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.update_clock();
+    }
+
+    /// Returns true if the game is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    fn make_move(&mut self, action: Move) -> Result<(), Error> {
+        let (row, col, value) = match action {
+            Move::Place { row, col, value } | Move::ToggleCandidate { row, col, value } => {
+                (row, col, value)
+            }
+            Move::Erase { row, col } => (row, col, 0),
+        };
+        self.board.check_placement((row, col, value))?;
+
+        let previous = self.board.values[row * 9 + col];
+        let marks = self.board.marks[row * 9 + col];
+        match action {
+            Move::Place { value, .. } if value == previous => return Ok(()),
+            Move::Place { value, .. } if value != self.solution.values[row * 9 + col] => {
+                self.mistakes += 1
+            }
+            Move::Erase { .. } if previous == 0 => return Ok(()),
+            _ => (),
+        }
+
+        let pruned = self.apply(action);
+        self.done.push(Command {
+            action,
+            previous,
+            marks,
+            pruned,
+        });
+        self.undone.clear();
+        self.update_clock();
+        Ok(())
+    }
+
+    /// Makes move on board. Returns cells whose marks were pruned.
+    fn apply(&mut self, action: Move) -> Vec<(usize, usize)> {
+        match action {
+            Move::Place { row, col, value } => {
+                return self
+                    .board
+                    .place_and_prune((row, col, value))
+                    .expect("Move was checked when made");
+            }
+            Move::Erase { row, col } => self.board.values[row * 9 + col] = 0,
+            Move::ToggleCandidate { row, col, value } => self.toggle(row, col, value),
+        }
+        Vec::new()
+    }
+
+    fn toggle(&mut self, row: usize, col: usize, value: u8) {
+        let mark = &mut self.board.marks[row * 9 + col][(value - 1) as usize];
+        *mark = 1 - *mark;
+    }
+
+    /// Runs the clock unless paused or completed.
+    fn update_clock(&mut self) {
+        let running = !self.paused && !self.is_complete();
+        match self.running_since {
+            Some(since) if !running => {
+                self.elapsed += since.elapsed();
+                self.running_since = None;
+            }
+            None if running => self.running_since = Some(Instant::now()),
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::generator::{Difficulty, Metadata};
+    use super::*;
+
+    static PUZZLE: &str =
+        "..3456789456789123789123456..4365897365897214897214365531642978642978531978531642";
+
+    fn session() -> GameSession {
+        // Rectangle of 1s and 2s in top left has two solutions: keep one
+        let mut board: SudokuBoard = PUZZLE.parse().unwrap();
+        board.place((0, 0, 1)).unwrap();
+        board.clues[0] = true;
+
+        GameSession::new(Puzzle {
+            board,
+            difficulty: Difficulty::Easy,
+            metadata: Metadata::default(),
+            seed: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn when_placing_wrong_value_then_count_mistake() {
+        let mut game = session();
+
+        game.place(0, 1, 4).unwrap();
+        assert_eq!(game.mistakes(), 1);
+        game.place(0, 1, 2).unwrap();
+        assert_eq!(game.mistakes(), 1);
+        assert_eq!(game.board().values[1], 2);
+    }
+
+    #[test]
+    fn when_undoing_and_redoing_then_restore_board() {
+        let mut game = session();
+        game.place(0, 1, 4).unwrap();
+        game.place(0, 1, 2).unwrap();
+        game.toggle_candidate(3, 0, 2).unwrap();
+        game.erase(0, 1).unwrap();
+        assert_eq!(game.moves().len(), 4);
+
+        assert_eq!(game.undo(), Some(Move::Erase { row: 0, col: 1 }));
+        assert_eq!(game.board().values[1], 2);
+        game.undo();
+        assert!(!game.board().has_mark(3, 0, 2));
+        game.undo();
+        assert_eq!(game.board().values[1], 4);
+        game.undo();
+        assert_eq!(game.board().values[1], 0);
+        assert_eq!(game.undo(), None);
+
+        assert_eq!(
+            game.redo(),
+            Some(Move::Place {
+                row: 0,
+                col: 1,
+                value: 4
+            })
+        );
+        assert_eq!(game.board().values[1], 4);
+        game.redo();
+        game.redo();
+        assert_eq!(game.board().get_marks(3, 0), vec![2]);
+        assert_eq!(game.mistakes(), 1);
+    }
+
+    #[test]
+    fn when_placing_value_then_prune_marks_until_undone() {
+        let mut game = session();
+        game.toggle_candidate(0, 1, 2).unwrap();
+        game.toggle_candidate(3, 0, 1).unwrap();
+        game.toggle_candidate(3, 0, 2).unwrap();
+
+        game.toggle_candidate(3, 1, 1).unwrap();
+
+        game.place(3, 1, 2).unwrap();
+        assert_eq!(game.board().get_marks(3, 0), vec![1]);
+        assert_eq!(game.board().get_marks(0, 1), Vec::<u8>::new());
+        assert_eq!(game.board().get_marks(3, 1), Vec::<u8>::new());
+
+        game.undo();
+        assert_eq!(game.board().get_marks(3, 0), vec![1, 2]);
+        assert_eq!(game.board().get_marks(0, 1), vec![2]);
+        assert_eq!(game.board().get_marks(3, 1), vec![1]);
+        game.redo();
+        assert_eq!(game.board().get_marks(3, 0), vec![1]);
+        assert_eq!(game.board().get_marks(3, 1), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn when_making_new_move_then_discard_undone_moves() {
+        let mut game = session();
+        game.place(0, 1, 4).unwrap();
+        game.undo();
+        assert!(game.can_redo());
+
+        game.toggle_candidate(0, 1, 2).unwrap();
+        assert!(!game.can_redo());
+        assert_eq!(game.redo(), None);
+    }
+
+    #[test]
+    fn when_board_is_filled_correctly_then_game_is_complete() {
+        let mut game = session();
+        game.place(0, 1, 2).unwrap();
+        game.place(3, 0, 2).unwrap();
+        assert!(!game.is_complete());
+
+        game.place(3, 1, 1).unwrap();
+        assert!(game.is_complete());
+        let elapsed = game.elapsed();
+        assert_eq!(game.elapsed(), elapsed);
+
+        game.undo();
+        assert!(!game.is_complete());
+    }
+
+    #[test]
+    fn when_paused_then_clock_stops() {
+        let mut game = session();
+        game.pause();
+        assert!(game.is_paused());
+        let elapsed = game.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(game.elapsed(), elapsed);
+
+        game.resume();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(game.elapsed() > elapsed);
+    }
+
+    #[test]
+    fn when_changing_clue_then_return_error() {
+        let mut game = session();
+
+        match game.place(0, 2, 1) {
+            Err(Error::CannotPlaceOnClue { row: 0, col: 2 }) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        assert!(game.erase(0, 0).is_err());
+        assert!(game.toggle_candidate(0, 9, 1).is_err());
+        assert!(!game.can_undo());
+    }
+}
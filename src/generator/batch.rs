@@ -0,0 +1,315 @@
+use super::super::canonical::canonical_values;
+use super::super::error::Error;
+use super::{Generator, Puzzle};
+use std::collections::{BTreeMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// Attempts each worker thread may run ahead of the next one to release.
+const AHEAD_PER_THREAD: u32 = 2;
+
+/// Result of a generator run, with the number of its attempt.
+type Attempt = (u32, Result<Puzzle, Error>);
+
+/// Puzzles of a batch, in the order of the attempts that generated them.
+///
+/// Results are released in attempt order, whatever the number of threads,
+/// so generators seeded from the attempt number give the same puzzles on
+/// every run. Puzzles equivalent to an earlier one are skipped.
+///
+/// Failed generator runs are yielded as errors, e.g.
+/// `Error::GenerationFailed`, and the batch continues with the next
+/// attempt, so callers can report them and keep iterating. A generator
+/// that panics fails its attempt with `Error::GeneratorPanicked`. Generators that
+/// fail on every attempt make the batch yield errors without end, so
+/// callers should give up after too many failures in a row.
+///
+/// Workers wait instead of running more than a few attempts ahead of the
+/// next one to release, so a slow attempt does not let results pile up.
+///
+/// Ends after the requested number of puzzles. Dropping it stops the
+/// worker threads after their current attempt.
+pub struct Batch {
+    receiver: Receiver<Attempt>,
+    stop: Arc<AtomicBool>,
+    window: Arc<Window>,
+    /// Results received ahead of the next attempt to release.
+    pending: BTreeMap<u32, Result<Puzzle, Error>>,
+    next_attempt: u32,
+    seen: HashSet<[u8; 81]>,
+    count: usize,
+}
+
+impl Iterator for Batch {
+    type Item = Result<Puzzle, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.seen.len() < self.count {
+            let result = match self.pending.remove(&self.next_attempt) {
+                Some(result) => result,
+                None => {
+                    // Receiver fails when all workers are gone
+                    let (attempt, result) = self.receiver.recv().ok()?;
+                    self.pending.insert(attempt, result);
+                    continue;
+                }
+            };
+            self.next_attempt += 1;
+            self.window.advance(self.next_attempt);
+
+            match result {
+                Ok(puzzle) => {
+                    if !self.seen.insert(canonical_values(&puzzle.board.values)) {
+                        debug!("Skipping puzzle equivalent to an earlier one");
+                        continue;
+                    }
+                    if self.seen.len() == self.count {
+                        self.stop.store(true, Ordering::SeqCst);
+                        self.window.wake();
+                    }
+                    return Some(Ok(puzzle));
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.window.wake();
+    }
+}
+
+/// Attempts the worker threads may run, ahead of the next one to release.
+struct Window {
+    /// Next attempt to release.
+    next: Mutex<u32>,
+    advanced: Condvar,
+    size: u32,
+}
+
+impl Window {
+    fn advance(&self, next: u32) {
+        *self.next.lock().unwrap() = next;
+        self.advanced.notify_all();
+    }
+
+    /// Wakes waiting workers, e.g. to let them see that the batch stopped.
+    fn wake(&self) {
+        // Taking the lock makes sure workers wait before they are notified
+        let _next = self.next.lock().unwrap();
+        self.advanced.notify_all();
+    }
+
+    /// Waits until `attempt` is within the window, or the batch stopped.
+    fn wait_for(&self, attempt: u32, stop: &AtomicBool) {
+        let mut next = self.next.lock().unwrap();
+        while attempt >= *next + self.size && !stop.load(Ordering::SeqCst) {
+            next = self.advanced.wait(next).unwrap();
+        }
+    }
+}
+
+/// State shared by the worker threads of a batch.
+struct Shared<F> {
+    new_generator: F,
+    attempts: AtomicU32,
+    stop: Arc<AtomicBool>,
+    window: Arc<Window>,
+}
+
+/// Generates `count` puzzles on `threads` worker threads, none of them
+/// equivalent to another, see `canonical`.
+///
+/// `new_generator` is called on the worker threads with the number of
+/// each attempt, counting from 0, and returns the generator for that
+/// attempt, e.g. `RandomSudoku` with its own `Solver` and a seed derived
+/// from the number. Equivalent puzzles are skipped, so generators must
+/// be able to create enough different puzzles. See `Batch` for the
+/// order of puzzles and how errors are returned.
+pub fn generate_batch<F, G>(count: usize, threads: usize, new_generator: F) -> Batch
+where
+    F: Fn(u32) -> G + Send + Sync + 'static,
+    G: Generator,
+{
+    let threads = threads.max(1);
+    let (sender, receiver) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let window = Arc::new(Window {
+        next: Mutex::new(0),
+        advanced: Condvar::new(),
+        size: threads as u32 * AHEAD_PER_THREAD,
+    });
+    let shared = Arc::new(Shared {
+        new_generator,
+        attempts: AtomicU32::new(0),
+        stop: stop.clone(),
+        window: window.clone(),
+    });
+
+    if count > 0 {
+        for _ in 0..threads {
+            let shared = shared.clone();
+            let sender = sender.clone();
+            thread::spawn(move || run_worker(&shared, &sender));
+        }
+    }
+
+    Batch {
+        receiver,
+        stop,
+        window,
+        pending: BTreeMap::new(),
+        next_attempt: 0,
+        seen: HashSet::new(),
+        count,
+    }
+}
+
+fn run_worker<F, G>(shared: &Shared<F>, sender: &Sender<Attempt>)
+where
+    F: Fn(u32) -> G,
+    G: Generator,
+{
+    while !shared.stop.load(Ordering::SeqCst) {
+        let attempt = shared.attempts.fetch_add(1, Ordering::SeqCst);
+        shared.window.wait_for(attempt, &shared.stop);
+        if shared.stop.load(Ordering::SeqCst) {
+            break;
+        }
+
+        // Batch waits for every attempt in order, so a panic must still send one
+        let result =
+            panic::catch_unwind(AssertUnwindSafe(|| (shared.new_generator)(attempt).run()))
+                .unwrap_or(Err(Error::GeneratorPanicked(attempt)));
+
+        // Receiver is gone when the batch was dropped
+        if sender.send((attempt, result)).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::super::canonical::canonical_form;
+    use super::super::super::generator::Difficulty;
+    use super::super::super::solver::bitboard::BitboardSolver;
+    use super::super::super::solver::Solver;
+    use super::super::random_gen::RandomSudoku;
+    use super::*;
+
+    /// Generator failing on odd attempts.
+    struct Flaky(u32);
+
+    impl Generator for Flaky {
+        fn run(&mut self) -> Result<Puzzle, Error> {
+            if self.0 % 2 == 1 {
+                return Err(Error::GenerationFailed {
+                    difficulty: Difficulty::Easy,
+                    attempts: 1,
+                });
+            }
+            RandomSudoku::new(BitboardSolver::new()).seed(self.0).run()
+        }
+    }
+
+    /// Generator panicking on every third attempt, starting with attempt 1.
+    struct Panicky(u32);
+
+    impl Generator for Panicky {
+        fn run(&mut self) -> Result<Puzzle, Error> {
+            if self.0 % 3 == 1 {
+                panic!("Attempt {}", self.0);
+            }
+            RandomSudoku::new(BitboardSolver::new()).seed(self.0).run()
+        }
+    }
+
+    fn lines(batch: Batch) -> Vec<String> {
+        batch
+            .map(|result| result.expect("Expected success").board.to_line())
+            .collect()
+    }
+
+    #[test]
+    fn when_generating_batch_then_return_count_distinct_puzzles() {
+        let puzzles: Vec<Puzzle> =
+            generate_batch(6, 3, |n| RandomSudoku::new(BitboardSolver::new()).seed(n))
+                .map(|result| result.expect("Expected success"))
+                .collect();
+
+        assert_eq!(puzzles.len(), 6);
+        let forms: HashSet<String> = puzzles
+            .iter()
+            .map(|puzzle| canonical_form(&puzzle.board).to_line())
+            .collect();
+        assert_eq!(forms.len(), 6);
+        for puzzle in &puzzles {
+            assert!(BitboardSolver::new().verify(&puzzle.board).is_valid());
+        }
+    }
+
+    #[test]
+    fn when_generating_seeded_batch_then_puzzles_do_not_depend_on_threads() {
+        let new_generator = |n| RandomSudoku::new(BitboardSolver::new()).seed(n);
+
+        let single = lines(generate_batch(5, 1, new_generator));
+        assert_eq!(lines(generate_batch(5, 4, new_generator)), single);
+        assert_eq!(lines(generate_batch(5, 4, new_generator)), single);
+    }
+
+    #[test]
+    fn when_generators_repeat_puzzles_then_skip_duplicates() {
+        // Only two different seeds
+        let puzzles: Vec<Puzzle> = generate_batch(2, 3, |n| {
+            RandomSudoku::new(BitboardSolver::new()).seed(n % 2)
+        })
+        .map(|result| result.expect("Expected success"))
+        .collect();
+
+        assert_eq!(puzzles.len(), 2);
+        assert_ne!(
+            canonical_form(&puzzles[0].board),
+            canonical_form(&puzzles[1].board)
+        );
+    }
+
+    #[test]
+    fn when_generator_fails_then_return_error_in_order_and_continue() {
+        let results: Vec<Result<Puzzle, Error>> = generate_batch(3, 3, Flaky).collect();
+
+        assert_eq!(results.len(), 5);
+        for (attempt, result) in results.iter().enumerate() {
+            assert_eq!(result.is_ok(), attempt % 2 == 0);
+        }
+    }
+
+    #[test]
+    fn when_generator_panics_then_return_error_for_attempt_and_continue() {
+        let results: Vec<Result<Puzzle, Error>> = generate_batch(4, 2, Panicky).collect();
+
+        assert_eq!(results.len(), 6);
+        for (attempt, result) in results.iter().enumerate() {
+            match result {
+                Err(Error::GeneratorPanicked(n)) => assert_eq!(*n as usize, attempt),
+                Ok(_) => assert_ne!(attempt % 3, 1),
+                Err(e) => panic!("Unexpected error: {}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn when_count_is_zero_then_batch_is_empty() {
+        let mut batch = generate_batch(0, 2, |n| RandomSudoku::new(BitboardSolver::new()).seed(n));
+
+        assert!(batch.next().is_none());
+    }
+}
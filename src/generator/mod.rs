@@ -1,10 +1,16 @@
 use super::board::SudokuBoard;
+use super::error::Error;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::convert::From;
 use std::fmt;
 
+pub mod batch;
+pub mod pattern_gen;
 pub mod random_gen;
 
 #[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Difficulty {
     Easy,
     Medium,
@@ -42,13 +48,66 @@ impl fmt::Display for Difficulty {
     }
 }
 
+/// Descriptive information about a puzzle, e.g. from puzzle files.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub source: Option<String>,
+    pub date: Option<String>,
+    /// Rating or difficulty level, as given by the puzzle source.
+    pub rating: Option<String>,
+    pub comments: Vec<String>,
+    /// Fields not covered above, by their single character tag.
+    pub other: Vec<(char, String)>,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Puzzle {
     pub board: SudokuBoard,
     pub difficulty: Difficulty,
+    pub metadata: Metadata,
+    /// Seed that generates the puzzle again, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seed: Option<u32>,
 }
 
 /// Sudoku puzzle generator.
 pub trait Generator {
     /// Generates sudoku puzzle or an error if generation fails.
-    fn run(&mut self) -> Result<Puzzle, String>;
+    fn run(&mut self) -> Result<Puzzle, Error>;
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn when_serializing_puzzle_then_board_is_compact_and_round_trips() {
+        let board: SudokuBoard =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......"
+                .parse()
+                .unwrap();
+        let puzzle = Puzzle {
+            board,
+            difficulty: Difficulty::Hard,
+            metadata: Metadata {
+                author: Some(String::from("Jane Doe")),
+                ..Metadata::default()
+            },
+            seed: Some(42),
+        };
+
+        let json = serde_json::to_string(&puzzle).unwrap();
+        assert!(json.contains("\"clues\":\"4.....8.5"));
+        assert!(json.contains("\"difficulty\":\"Hard\""));
+
+        let result: Puzzle = serde_json::from_str(&json).unwrap();
+        assert_eq!(result.board.to_line(), puzzle.board.to_line());
+        assert_eq!(result.difficulty, Difficulty::Hard);
+        assert_eq!(result.metadata, puzzle.metadata);
+        assert_eq!(result.seed, Some(42));
+    }
 }
@@ -0,0 +1,225 @@
+use super::super::board::SudokuBoard;
+use super::super::error::Error;
+use super::super::solver::{SolutionCount, Solver, Verification};
+use super::random_gen::{
+    convert_to_clues, get_board_with_clues, get_difficulty, seed_array, seed_of,
+};
+use super::{Generator, Metadata, Puzzle};
+use rand::prelude::*;
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64Mcg;
+
+/// Fewest clues of any Sudoku puzzle with a unique solution.
+static MIN_CLUES: usize = 17;
+
+/// Number of clue changes tried on each solved board.
+static REPAIR_STEPS: u32 = 200;
+
+/// Solutions counted when comparing clue changes.
+static COUNT_LIMIT: u32 = 100;
+
+/// Generator for puzzles with clues at fixed positions,
+/// e.g. in the shape of a letter or a heart.
+///
+/// Fills the positions with the values of a random solved board, then
+/// changes clues one at a time, keeping changes that do not increase the
+/// number of solutions, until the puzzle has a unique solution. Starts
+/// over with another solved board if that takes too many changes.
+/// Patterns with few clues may need many attempts, or none may succeed,
+/// and patterns with fewer than 17 clues fail with `Error::InvalidOptions`.
+///
+/// Counting solutions of sparse boards is slow for `LeastOptionsSolver`,
+/// so `BitboardSolver` is recommended.
+pub struct PatternGenerator {
+    solver: Box<dyn Solver>,
+    pattern: [bool; 81],
+    random_seed: bool,
+    seed: [u8; 16],
+    max_attempts: u32,
+    max_iterations: u32,
+}
+
+impl Generator for PatternGenerator {
+    fn run(&mut self) -> Result<Puzzle, Error> {
+        let clues = self.pattern.iter().filter(|&&clue| clue).count();
+        if clues < MIN_CLUES {
+            return Err(Error::InvalidOptions(format!(
+                "pattern has {} clues, a unique puzzle needs at least {}",
+                clues, MIN_CLUES
+            )));
+        }
+
+        // Draw seed that can be reported with the puzzle
+        if self.random_seed {
+            self.seed = seed_array(Pcg64Mcg::from_entropy().gen());
+        }
+        let mut rng = Pcg64Mcg::from_seed(self.seed);
+
+        for attempt in 0..self.max_attempts {
+            let mut board = self.solve_with_random_clues(&mut rng);
+            for (value, &clue) in board.values.iter_mut().zip(self.pattern.iter()) {
+                if !clue {
+                    *value = 0;
+                }
+            }
+            self.repair(&mut board, &mut rng);
+
+            if let Verification::ValidWithBranches(branches) = self.solver.verify(&board) {
+                info!("Pattern filled after {} attempts", attempt + 1);
+                convert_to_clues(&mut board);
+                return Ok(Puzzle {
+                    board,
+                    difficulty: get_difficulty(81 - clues as u32, branches),
+                    metadata: Metadata::default(),
+                    seed: Some(seed_of(&self.seed)),
+                });
+            }
+        }
+
+        Err(Error::PatternFailed {
+            clues,
+            attempts: self.max_attempts,
+        })
+    }
+}
+
+impl PatternGenerator {
+    /// Creates new `PatternGenerator` with clues at positions
+    /// marked true in `pattern`, and specified `Solver`.
+    ///
+    /// Can be further customized with builder methods
+    /// `seed` and `attempts`.
+    pub fn new<T>(solver: T, pattern: [bool; 81]) -> PatternGenerator
+    where
+        T: Solver + 'static,
+    {
+        PatternGenerator {
+            solver: Box::new(solver),
+            pattern,
+            random_seed: true,
+            seed: [0; 16],
+            max_attempts: 10_000,
+            max_iterations: 1000,
+        }
+    }
+
+    /// Sets random seed to use for puzzle generation.
+    ///
+    /// Using same seed and pattern will generate the exact same puzzle.
+    pub fn seed(mut self, seed: u32) -> PatternGenerator {
+        self.random_seed = false;
+        self.seed = seed_array(seed);
+
+        debug!("Using seed {}", seed);
+
+        self
+    }
+
+    /// Sets number of solved boards to try before giving up.
+    ///
+    /// Default is 10000.
+    pub fn attempts(mut self, attempts: u32) -> PatternGenerator {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Changes clues towards a unique solution, keeping the board solvable.
+    fn repair(&mut self, board: &mut SudokuBoard, rng: &mut Pcg64Mcg) {
+        let cells: Vec<usize> = (0..81).filter(|&index| self.pattern[index]).collect();
+        let mut count = self.solver.count_solutions(board, COUNT_LIMIT).count();
+
+        for _ in 0..REPAIR_STEPS {
+            if count <= 1 {
+                return;
+            }
+
+            let index = *cells.choose(rng).unwrap();
+            let (row, col) = (index / 9, index % 9);
+            let old = board.values[index];
+            board.place((row, col, 0)).unwrap();
+            let allowed = board.get_allowed_vals(row, col);
+            let values: Vec<u8> = (1..10)
+                .filter(|&val| val != old && allowed[val as usize - 1] == 1)
+                .collect();
+
+            if let Some(&value) = values.choose(rng) {
+                board.place((row, col, value)).unwrap();
+                match self.solver.count_solutions(board, COUNT_LIMIT) {
+                    SolutionCount::Exactly(n) | SolutionCount::AtLeast(n)
+                        if n > 0 && n <= count =>
+                    {
+                        count = n;
+                        continue;
+                    }
+                    _ => (),
+                }
+            }
+            board.place((row, col, old)).unwrap();
+        }
+    }
+
+    fn solve_with_random_clues(&mut self, rng: &mut Pcg64Mcg) -> SudokuBoard {
+        loop {
+            let board = get_board_with_clues(rng);
+            if let Ok(solution) = self.solver.try_solve(&board, Some(self.max_iterations)) {
+                return solution.board;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::super::solver::bitboard::BitboardSolver;
+    use super::*;
+
+    /// Heart shape of 27 clues.
+    static HEART: &str = "\
+        .xx...xx.\
+        x..x.x..x\
+        x...x...x\
+        x.......x\
+        .x.....x.\
+        ..x...x..\
+        ...x.x...\
+        ....x....\
+        .xxxxxxx.";
+
+    fn pattern(text: &str) -> [bool; 81] {
+        let mut pattern = [false; 81];
+        for (cell, c) in pattern.iter_mut().zip(text.chars()) {
+            *cell = c == 'x';
+        }
+        pattern
+    }
+
+    #[test]
+    fn when_filling_pattern_then_clues_match_pattern_and_puzzle_is_unique() {
+        let pattern = pattern(HEART);
+        let puzzle = PatternGenerator::new(BitboardSolver::new(), pattern)
+            .seed(3)
+            .run()
+            .expect("Expected success");
+
+        assert_eq!(puzzle.board.clues[..], pattern[..]);
+        for (&value, &clue) in puzzle.board.values.iter().zip(pattern.iter()) {
+            assert_eq!(value > 0, clue);
+        }
+        assert!(BitboardSolver::new().verify(&puzzle.board).is_valid());
+        assert_eq!(puzzle.seed, Some(3));
+    }
+
+    #[test]
+    fn when_pattern_has_too_few_clues_then_return_error() {
+        let mut pattern = [false; 81];
+        for cell in pattern.iter_mut().take(16) {
+            *cell = true;
+        }
+
+        match PatternGenerator::new(BitboardSolver::new(), pattern).run() {
+            Err(Error::InvalidOptions(_)) => (),
+            other => panic!("Unexpected result: {:?}", other.map(|p| p.board)),
+        }
+    }
+}
@@ -1,37 +1,126 @@
 use super::super::board::SudokuBoard;
-use super::super::solver::{Solver, Verification};
-use super::{Difficulty, Generator, Puzzle};
+use super::super::error::Error;
+use super::super::rating::Rater;
+use super::super::solver::{is_minimal, Solver, Verification};
+use super::{Difficulty, Generator, Metadata, Puzzle};
 use rand::prelude::*;
 use rand::seq::SliceRandom;
 use rand_pcg::Pcg64Mcg;
+use std::ops::RangeInclusive;
 
 /// Number of attempts of creating board with valid
 /// set of clues
 static CREATE_CLUE_ATTEMPTS: u32 = 5;
 
+/// Number of times a clue is re-added to reach the maximum number
+/// of clues, in each attempt
+static READD_STEPS: u32 = 100;
+
+/// Measure used for the `Difficulty` of generated puzzles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DifficultyMeasure {
+    /// Number of clues and branches needed by the solver.
+    Branches,
+    /// Techniques needed by a logical solver, see `Rater`.
+    Techniques,
+}
+
+/// Symmetry of the clue positions of generated puzzles.
+///
+/// Clues are removed in orbits, i.e. a cell together with all cells
+/// it maps to under the symmetry, so that the clue pattern is unchanged
+/// when rotated or mirrored.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Symmetry {
+    /// Clues are placed anywhere.
+    None,
+    /// Pattern is unchanged when rotated by 180 degrees.
+    Rotational180,
+    /// Pattern is unchanged when rotated by 90 degrees.
+    Rotational90,
+    /// Pattern is mirrored across the middle row.
+    Horizontal,
+    /// Pattern is mirrored across the middle column.
+    Vertical,
+    /// Pattern is mirrored across the diagonal from top left to bottom right.
+    Diagonal,
+    /// Pattern is mirrored across the diagonal from top right to bottom left.
+    AntiDiagonal,
+    /// Pattern has all of the above symmetries.
+    Full,
+}
+
+impl Symmetry {
+    /// Returns board indices of the orbit of cell at `index`,
+    /// starting with `index`.
+    pub fn orbit(self, index: usize) -> Vec<usize> {
+        let (r, c) = (index / 9, index % 9);
+        let images = match self {
+            Symmetry::None => vec![],
+            Symmetry::Rotational180 => vec![(8 - r, 8 - c)],
+            Symmetry::Rotational90 => vec![(c, 8 - r), (8 - r, 8 - c), (8 - c, r)],
+            Symmetry::Horizontal => vec![(8 - r, c)],
+            Symmetry::Vertical => vec![(r, 8 - c)],
+            Symmetry::Diagonal => vec![(c, r)],
+            Symmetry::AntiDiagonal => vec![(8 - c, 8 - r)],
+            Symmetry::Full => vec![
+                (c, 8 - r),
+                (8 - r, 8 - c),
+                (8 - c, r),
+                (8 - r, c),
+                (r, 8 - c),
+                (c, r),
+                (8 - c, 8 - r),
+            ],
+        };
+
+        let mut orbit = vec![index];
+        for (row, col) in images {
+            if !orbit.contains(&(row * 9 + col)) {
+                orbit.push(row * 9 + col);
+            }
+        }
+        orbit
+    }
+}
+
 /// Generator for creating random sudoku puzzle.
 ///
-/// Allows specifying random seed and `Difficulty`.
+/// Allows specifying random seed, `Difficulty`, `DifficultyMeasure`,
+/// `Symmetry`, number of clues and whether puzzles must be minimal.
 pub struct RandomSudoku {
-    solver: Box<Solver>,
+    solver: Box<dyn Solver>,
     random_seed: bool,
     seed: [u8; 16],
     difficulty: Difficulty,
     max_iterations: u32,
+    rater: Option<Rater>,
+    symmetry: Symmetry,
+    minimal: bool,
+    clues: RangeInclusive<usize>,
 }
 
 impl Generator for RandomSudoku {
-    fn run(&mut self) -> Result<Puzzle, String> {
+    fn run(&mut self) -> Result<Puzzle, Error> {
+        self.check()?;
         // Set up random generator
         let mut rng = self.random_generator();
         // Randomize clues and solve board
         let mut board = self.solve_with_random_clues(&mut rng);
         // Find valid puzzle, if possible
         self.find_valid_puzzle(&mut board, &mut rng)?;
-        // Return puzzle
+        // Include technique rating score, if used
+        let mut metadata = Metadata::default();
+        if let Some(ref mut rater) = self.rater {
+            let rating = rater.rate(&board)?;
+            metadata.rating = Some(format!("{:.1}", rating.score));
+        }
+        // Return puzzle, with seed that generates it again
         Ok(Puzzle {
             board,
             difficulty: self.difficulty,
+            metadata,
+            seed: Some(seed_of(&self.seed)),
         })
     }
 }
@@ -42,7 +131,7 @@ impl RandomSudoku {
     /// and specified `Solver`.
     ///
     /// Can be further customized with builder methods
-    /// `seed` and `difficulty`.
+    /// `seed`, `difficulty`, `measure`, `symmetry`, `minimal` and `clues`.
     pub fn new<T>(solver: T) -> RandomSudoku
     where
         T: Solver + 'static,
@@ -54,6 +143,10 @@ impl RandomSudoku {
             seed: [0; 16],
             difficulty: Difficulty::Easy,
             max_iterations: 1000,
+            rater: None,
+            symmetry: Symmetry::None,
+            minimal: false,
+            clues: 0..=81,
         }
     }
 
@@ -62,19 +155,8 @@ impl RandomSudoku {
     /// Using same seed (and same `Difficulty`) will generate
     /// the exact same puzzle.
     pub fn seed(mut self, seed: u32) -> RandomSudoku {
-        // Build array from u32
-        let seed_bytes: [u8; 4] = [
-            (seed >> 24) as u8,
-            (seed >> 16) as u8,
-            (seed >> 8) as u8,
-            seed as u8,
-        ];
         self.random_seed = false;
-        // Duplicate u32 seed to fill up seed array
-        // TODO: Improve, this might impact randomness of generator
-        for (i, pos) in self.seed.iter_mut().enumerate() {
-            *pos = seed_bytes[i % 4];
-        }
+        self.seed = seed_array(seed);
 
         debug!(
             "Using seed {}",
@@ -94,12 +176,120 @@ impl RandomSudoku {
 
         self
     }
+
+    /// Sets how `Difficulty` of puzzle is measured.
+    ///
+    /// Default is `DifficultyMeasure::Branches`.
+    pub fn measure(mut self, measure: DifficultyMeasure) -> RandomSudoku {
+        self.rater = match measure {
+            DifficultyMeasure::Branches => None,
+            DifficultyMeasure::Techniques => Some(Rater::new()),
+        };
+
+        debug!("Using difficulty measure: {:?}", measure);
+
+        self
+    }
+
+    /// Sets `Symmetry` of clue positions.
+    ///
+    /// Default is `Symmetry::None`. Symmetric puzzles have fewer ways
+    /// to remove clues, so generation may need more attempts.
+    pub fn symmetry(mut self, symmetry: Symmetry) -> RandomSudoku {
+        self.symmetry = symmetry;
+
+        debug!("Using symmetry: {:?}", symmetry);
+
+        self
+    }
+
+    /// Sets whether every clue of the puzzle must be needed for
+    /// its unique solution, see `solver::is_minimal`.
+    ///
+    /// Default is false, where generation stops removing clues as soon
+    /// as a removal makes the puzzle harder than the target `Difficulty`.
+    ///
+    /// Minimal puzzles cannot have a `Symmetry`, as removing clues in
+    /// symmetric groups leaves clues that could be removed one at a time.
+    /// They have few clues, so the target `Difficulty` must be `Hard` or
+    /// `Evil`: with `DifficultyMeasure::Branches` they are never easier,
+    /// and with `DifficultyMeasure::Techniques` easier ones are too rare
+    /// to find. Other combinations fail with `Error::InvalidOptions`,
+    /// see `check`.
+    pub fn minimal(mut self, minimal: bool) -> RandomSudoku {
+        self.minimal = minimal;
+
+        debug!("Using minimal puzzles: {}", minimal);
+
+        self
+    }
+
+    /// Sets range of number of clues of puzzle, e.g. `24..=28`.
+    ///
+    /// Clues are not removed below the minimum, and removals making the
+    /// puzzle harder than the target `Difficulty` are skipped while there
+    /// are more clues than the maximum. Note that `DifficultyMeasure::Branches`
+    /// rates puzzles by their number of clues, so the range must allow
+    /// the target `Difficulty`: 35 or more clues for `Easy` and 28-34 for
+    /// `Medium`. Other ranges fail with `Error::InvalidOptions`, see `check`.
+    pub fn clues(mut self, clues: RangeInclusive<usize>) -> RandomSudoku {
+        debug!("Using clues: {:?}", clues);
+
+        self.clues = clues;
+        self
+    }
+
+    /// Checks that the options set can be combined.
+    ///
+    /// `run` fails with the same error, so this allows rejecting
+    /// options before generating, e.g. when parsing them.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.minimal && self.symmetry != Symmetry::None {
+            return Err(Error::InvalidOptions(String::from(
+                "minimal puzzles cannot be symmetric",
+            )));
+        }
+        if self.minimal && self.difficulty < Difficulty::Hard {
+            return Err(Error::InvalidOptions(format!(
+                "minimal puzzles cannot be {}",
+                self.difficulty
+            )));
+        }
+
+        let (min, max) = (*self.clues.start(), *self.clues.end());
+        if min > max || max > 81 {
+            return Err(Error::InvalidOptions(format!(
+                "clue range {}-{} is not within 0-81",
+                min, max
+            )));
+        }
+        // Branches rate puzzles without branches by their clues
+        let needed = match self.difficulty {
+            Difficulty::Easy => Some(35..=81),
+            Difficulty::Medium => Some(28..=34),
+            _ => None,
+        };
+        if let (None, Some(needed)) = (&self.rater, needed) {
+            if max < *needed.start() || min > *needed.end() {
+                return Err(Error::InvalidOptions(format!(
+                    "{} puzzles measured by branches need {}-{} clues, not {}-{}",
+                    self.difficulty,
+                    needed.start(),
+                    needed.end(),
+                    min,
+                    max
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl RandomSudoku {
     fn random_generator(&mut self) -> Pcg64Mcg {
+        // Draw seed that can be reported with the puzzle
         if self.random_seed {
-            Pcg64Mcg::from_entropy().fill_bytes(&mut self.seed);
+            self.seed = seed_array(Pcg64Mcg::from_entropy().gen());
         }
         Pcg64Mcg::from_seed(self.seed)
     }
@@ -109,7 +299,7 @@ impl RandomSudoku {
         loop {
             board = get_board_with_clues(rng);
 
-            let result = self.solver.try_solve(&mut board, Some(self.max_iterations));
+            let result = self.solver.try_solve(&board, Some(self.max_iterations));
 
             if let Ok(solution) = result {
                 board = solution.board;
@@ -124,34 +314,58 @@ impl RandomSudoku {
         &mut self,
         board: &mut SudokuBoard,
         rng: &mut Pcg64Mcg,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         let orig_values = board.values;
 
         for _ in 0..CREATE_CLUE_ATTEMPTS {
             let mut removal_sequence: Vec<usize> = (0..81).collect();
             removal_sequence.shuffle(rng);
 
-            let mut count = 0;
+            let orbits = self.removal_orbits(&removal_sequence);
             let mut removed_cells = 0;
             let mut diff = Difficulty::Easy;
+            self.remove_orbits(board, &orbits, &mut removed_cells, &mut diff);
 
-            while count < 81 {
-                let index = removal_sequence[count];
-                if board.values[index] > 0 && !board.clues[index] {
-                    count += 1;
-
-                    if self.try_removing_value(board, index, &mut removed_cells, &mut diff) {
-                        break;
-                    }
+            // Re-add clues when removal got stuck above the maximum
+            // number of clues, and continue removing in another order
+            let mut steps = 0;
+            while 81 - removed_cells as usize > *self.clues.end() && steps < READD_STEPS {
+                steps += 1;
+                let removed: Vec<&Vec<usize>> = orbits
+                    .iter()
+                    .filter(|orbit| board.values[orbit[0]] == 0)
+                    .collect();
+                let readded = match removed.choose(rng) {
+                    Some(&orbit) => orbit.clone(),
+                    None => break,
+                };
+                for &index in &readded {
+                    board
+                        .place((index / 9, index % 9, orig_values[index]))
+                        .unwrap();
                 }
+                removed_cells -= readded.len() as u32;
+
+                let mut others: Vec<Vec<usize>> = orbits
+                    .iter()
+                    .filter(|&orbit| *orbit != readded)
+                    .cloned()
+                    .collect();
+                others.shuffle(rng);
+                self.remove_orbits(board, &others, &mut removed_cells, &mut diff);
             }
 
-            if diff >= self.difficulty {
-                info!(
-                    "Valid puzzle found. Clues: {}, difficulty: {}",
-                    81 - removed_cells,
-                    diff
-                );
+            // Removals making the puzzle too hard are skipped when minimal,
+            // or above the maximum number of clues, which may leave
+            // redundant clues or too many clues
+            let clues = 81 - removed_cells as usize;
+            let minimal = !self.minimal || {
+                let mut puzzle = board.clone();
+                convert_to_clues(&mut puzzle);
+                is_minimal(&puzzle, &mut *self.solver)
+            };
+            if diff >= self.difficulty && minimal && self.clues.contains(&clues) {
+                info!("Valid puzzle found. Clues: {}, difficulty: {}", clues, diff);
                 convert_to_clues(board);
                 return Ok(());
             } else {
@@ -160,40 +374,123 @@ impl RandomSudoku {
             }
         }
 
-        Err(format!(
-            "Could not generate puzzle of difficulty {} after {} attempts",
-            self.difficulty,
-            CREATE_CLUE_ATTEMPTS
-        ))
+        Err(Error::GenerationFailed {
+            difficulty: self.difficulty,
+            attempts: CREATE_CLUE_ATTEMPTS,
+        })
     }
 
-    fn try_removing_value(
+    /// Removes orbits in order while the puzzle stays unique, keeping
+    /// within the range of clues and not harder than the target.
+    fn remove_orbits(
         &mut self,
         board: &mut SudokuBoard,
-        index: usize,
+        orbits: &[Vec<usize>],
+        removed_cells: &mut u32,
+        diff: &mut Difficulty,
+    ) {
+        for orbit in orbits {
+            let clues = 81 - *removed_cells as usize;
+            let removable = clues >= self.clues.start() + orbit.len()
+                && orbit
+                    .iter()
+                    .all(|&index| board.values[index] > 0 && !board.clues[index]);
+            if removable
+                && self.try_removing_values(board, orbit, removed_cells, diff)
+                && !self.minimal
+                && clues <= *self.clues.end()
+            {
+                break;
+            }
+        }
+    }
+
+    /// Groups shuffled cells into orbits of the symmetry, in order of
+    /// their first cell.
+    fn removal_orbits(&self, removal_sequence: &[usize]) -> Vec<Vec<usize>> {
+        let mut seen = [false; 81];
+        let mut orbits = Vec::new();
+        for &index in removal_sequence {
+            if !seen[index] {
+                let orbit = self.symmetry.orbit(index);
+                for &cell in &orbit {
+                    seen[cell] = true;
+                }
+                orbits.push(orbit);
+            }
+        }
+        orbits
+    }
+
+    /// Removes values of cells together, keeping them removed
+    /// if the puzzle stays unique and not harder than the target.
+    ///
+    /// Returns true if removing them makes the puzzle too hard.
+    fn try_removing_values(
+        &mut self,
+        board: &mut SudokuBoard,
+        indices: &[usize],
         removed_cells: &mut u32,
         diff: &mut Difficulty,
     ) -> bool {
-        let num = board.values[index];
-        let (row, col) = (index / 9, index % 9);
-        board.place((row, col, 0)).unwrap();
-        if let Verification::ValidWithBranches(branches) = self.solver.verify(&board) {
-            *removed_cells += 1;
+        let nums: Vec<u8> = indices.iter().map(|&index| board.values[index]).collect();
+        let restore = |board: &mut SudokuBoard| {
+            for (&index, &num) in indices.iter().zip(nums.iter()) {
+                board.place((index / 9, index % 9, num)).unwrap();
+            }
+        };
+        for &index in indices {
+            board.place((index / 9, index % 9, 0)).unwrap();
+        }
+
+        if let Verification::ValidWithBranches(branches) = self.solver.verify(board) {
+            *removed_cells += indices.len() as u32;
             let prev_diff = *diff;
-            *diff = get_difficulty(*removed_cells, branches);
+            *diff = match self.rater {
+                Some(ref mut rater) => rater
+                    .rate(board)
+                    .map_or(Difficulty::Evil, |rating| rating.difficulty),
+                None => get_difficulty(*removed_cells, branches),
+            };
             if *diff > self.difficulty {
                 *diff = prev_diff;
-                board.place((row, col, num)).unwrap();
+                *removed_cells -= indices.len() as u32;
+                restore(board);
                 return true;
             }
         } else {
-            board.place((row, col, num)).unwrap();
+            restore(board);
         }
         false
     }
 }
 
-fn get_difficulty(removed: u32, branches: u32) -> Difficulty {
+/// Builds random generator seed from `u32` seed.
+pub(super) fn seed_array(seed: u32) -> [u8; 16] {
+    // Build array from u32
+    let seed_bytes: [u8; 4] = [
+        (seed >> 24) as u8,
+        (seed >> 16) as u8,
+        (seed >> 8) as u8,
+        seed as u8,
+    ];
+    // Duplicate u32 seed to fill up seed array
+    // TODO: Improve, this might impact randomness of generator
+    let mut array = [0; 16];
+    for (i, pos) in array.iter_mut().enumerate() {
+        *pos = seed_bytes[i % 4];
+    }
+    array
+}
+
+/// Returns `u32` seed that `seed_array` built the array from.
+pub(super) fn seed_of(array: &[u8; 16]) -> u32 {
+    array[..4]
+        .iter()
+        .fold(0, |seed, &b| (seed << 8) | u32::from(b))
+}
+
+pub(super) fn get_difficulty(removed: u32, branches: u32) -> Difficulty {
     let clues = 81 - removed;
     match (clues, branches) {
         (_, b) if b > 1 => Difficulty::Evil,
@@ -203,7 +500,7 @@ fn get_difficulty(removed: u32, branches: u32) -> Difficulty {
     }
 }
 
-fn convert_to_clues(board: &mut SudokuBoard) {
+pub(super) fn convert_to_clues(board: &mut SudokuBoard) {
     for (i, clue) in board.clues.iter_mut().enumerate() {
         if board.values[i] > 0 {
             *clue = true;
@@ -211,7 +508,7 @@ fn convert_to_clues(board: &mut SudokuBoard) {
     }
 }
 
-fn get_board_with_clues(rng: &mut Pcg64Mcg) -> SudokuBoard {
+pub(super) fn get_board_with_clues(rng: &mut Pcg64Mcg) -> SudokuBoard {
     let mut board = SudokuBoard::with_clues(&[]);
     let mut add_sequence: Vec<usize> = (0..81).collect();
 
@@ -243,3 +540,163 @@ fn get_board_with_clues(rng: &mut Pcg64Mcg) -> SudokuBoard {
 
     board
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::super::solver::bitboard::BitboardSolver;
+    use super::super::super::solver::least_options::LeastOptionsSolver;
+    use super::*;
+
+    #[test]
+    fn when_measuring_by_techniques_then_puzzle_rating_matches_difficulty() {
+        for &difficulty in &[Difficulty::Easy, Difficulty::Medium] {
+            let puzzle = RandomSudoku::new(LeastOptionsSolver::new())
+                .seed(7)
+                .difficulty(difficulty)
+                .measure(DifficultyMeasure::Techniques)
+                .run()
+                .expect("Expected success");
+
+            let rating = Rater::new().rate(&puzzle.board).unwrap();
+            assert_eq!(rating.difficulty, difficulty);
+            assert_eq!(puzzle.metadata.rating, Some(format!("{:.1}", rating.score)));
+            assert_eq!(puzzle.seed, Some(7));
+        }
+    }
+
+    #[test]
+    fn when_seed_is_random_then_puzzle_records_seed_that_generates_it_again() {
+        let puzzle = RandomSudoku::new(BitboardSolver::new())
+            .run()
+            .expect("Expected success");
+        let seed = puzzle.seed.expect("Expected seed");
+
+        let again = RandomSudoku::new(BitboardSolver::new())
+            .seed(seed)
+            .run()
+            .expect("Expected success");
+        assert_eq!(again.board, puzzle.board);
+    }
+
+    #[test]
+    fn when_symmetry_is_set_then_clue_pattern_is_symmetric() {
+        let symmetries = [
+            Symmetry::Rotational180,
+            Symmetry::Rotational90,
+            Symmetry::Horizontal,
+            Symmetry::Vertical,
+            Symmetry::Diagonal,
+            Symmetry::AntiDiagonal,
+            Symmetry::Full,
+        ];
+        for &symmetry in &symmetries {
+            let puzzle = RandomSudoku::new(BitboardSolver::new())
+                .seed(11)
+                .symmetry(symmetry)
+                .run()
+                .expect("Expected success");
+
+            for index in 0..81 {
+                for cell in symmetry.orbit(index) {
+                    assert_eq!(puzzle.board.clues[cell], puzzle.board.clues[index]);
+                }
+            }
+            assert!(BitboardSolver::new().verify(&puzzle.board).is_valid());
+        }
+    }
+
+    #[test]
+    fn when_minimal_then_every_clue_is_needed() {
+        let puzzle = RandomSudoku::new(BitboardSolver::new())
+            .seed(5)
+            .difficulty(Difficulty::Hard)
+            .minimal(true)
+            .run()
+            .expect("Expected success");
+
+        assert!(is_minimal(&puzzle.board, &mut BitboardSolver::new()));
+    }
+
+    #[test]
+    fn when_minimal_is_combined_with_symmetry_or_easy_target_then_reject_options() {
+        let assert_rejected = |mut generator: RandomSudoku| {
+            assert!(generator.check().is_err());
+            match generator.run() {
+                Err(Error::InvalidOptions(_)) => (),
+                other => panic!("Unexpected result: {:?}", other.map(|p| p.board)),
+            }
+        };
+
+        assert_rejected(
+            RandomSudoku::new(BitboardSolver::new())
+                .difficulty(Difficulty::Hard)
+                .symmetry(Symmetry::Rotational180)
+                .minimal(true),
+        );
+        assert_rejected(RandomSudoku::new(BitboardSolver::new()).minimal(true));
+        assert_rejected(
+            RandomSudoku::new(BitboardSolver::new())
+                .difficulty(Difficulty::Medium)
+                .measure(DifficultyMeasure::Techniques)
+                .minimal(true),
+        );
+    }
+
+    #[test]
+    fn when_clue_range_cannot_reach_difficulty_then_reject_options() {
+        let check = |difficulty, range| {
+            RandomSudoku::new(BitboardSolver::new())
+                .difficulty(difficulty)
+                .clues(range)
+                .check()
+        };
+
+        assert!(check(Difficulty::Hard, RangeInclusive::new(30, 20)).is_err());
+        assert!(check(Difficulty::Easy, 40..=90).is_err());
+        assert!(check(Difficulty::Easy, 20..=25).is_err());
+        assert!(check(Difficulty::Medium, 20..=27).is_err());
+        assert!(check(Difficulty::Medium, 35..=40).is_err());
+        assert!(check(Difficulty::Medium, 25..=30).is_ok());
+        assert!(check(Difficulty::Hard, 20..=25).is_ok());
+
+        let rated = RandomSudoku::new(BitboardSolver::new())
+            .difficulty(Difficulty::Easy)
+            .measure(DifficultyMeasure::Techniques)
+            .clues(20..=25);
+        assert!(rated.check().is_ok());
+    }
+
+    #[test]
+    fn when_clues_are_set_then_number_of_clues_is_in_range() {
+        let ranges = [
+            (Difficulty::Hard, 24..=28),
+            (Difficulty::Hard, 22..=22),
+            (Difficulty::Medium, 30..=30),
+            (Difficulty::Easy, 40..=45),
+        ];
+        for (difficulty, range) in ranges.iter().cloned() {
+            let puzzle = RandomSudoku::new(BitboardSolver::new())
+                .seed(13)
+                .difficulty(difficulty)
+                .clues(range.clone())
+                .run()
+                .expect("Expected success");
+
+            let clues = puzzle.board.clues.iter().filter(|&&clue| clue).count();
+            assert!(range.contains(&clues), "{} clues", clues);
+            assert!(BitboardSolver::new().verify(&puzzle.board).is_valid());
+        }
+    }
+
+    #[test]
+    fn when_getting_orbit_then_include_every_image_once() {
+        assert_eq!(Symmetry::None.orbit(10), vec![10]);
+        assert_eq!(Symmetry::Rotational180.orbit(40), vec![40]);
+        assert_eq!(Symmetry::Rotational180.orbit(0), vec![0, 80]);
+        assert_eq!(Symmetry::Rotational90.orbit(1), vec![1, 17, 79, 63]);
+        assert_eq!(Symmetry::Diagonal.orbit(10), vec![10]);
+        assert_eq!(Symmetry::Full.orbit(1).len(), 8);
+        assert_eq!(Symmetry::Full.orbit(0).len(), 4);
+    }
+}
@@ -5,8 +5,17 @@ extern crate rand_pcg;
 #[macro_use]
 extern crate log;
 extern crate env_logger;
+#[cfg(feature = "serde")]
+extern crate serde;
 
 pub mod board;
+pub mod canonical;
+mod error;
+pub mod formats;
+pub mod game;
 pub mod generator;
 pub mod group;
+pub mod rating;
 pub mod solver;
+
+pub use error::Error;
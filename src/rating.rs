@@ -0,0 +1,185 @@
+//! Puzzle difficulty rating based on the techniques needed to solve it.
+
+use super::board::SudokuBoard;
+use super::error::Error;
+use super::generator::Difficulty;
+use super::solver::logical::LogicalSolver;
+use super::solver::Solver;
+
+/// Score of puzzles that cannot be solved without guessing.
+pub static GUESSING_SCORE: f32 = 10.0;
+
+/// Highest score of `Difficulty::Easy` puzzles (singles only).
+static EASY_MAX: f32 = 2.3;
+/// Highest score of `Difficulty::Medium` puzzles.
+static MEDIUM_MAX: f32 = 3.4;
+
+/// Score added for each application of a technique harder than singles,
+/// after the first one.
+static STEP_SCORE: f32 = 0.1;
+/// Maximum score added for technique applications.
+static MAX_STEPS_SCORE: f32 = 0.5;
+
+/// Difficulty rating of a puzzle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rating {
+    /// Score of the puzzle, see `Rater`.
+    pub score: f32,
+    /// Hardest technique needed, or `None` if no deductions were needed.
+    pub hardest: Option<&'static str>,
+    /// Number of deductions needed to solve the puzzle, or to get stuck.
+    pub steps: u32,
+    /// True if the puzzle cannot be solved without guessing.
+    pub requires_guessing: bool,
+    /// Difficulty bucket of the score.
+    pub difficulty: Difficulty,
+}
+
+/// Rates puzzles by solving them with a `LogicalSolver`.
+///
+/// # Remarks
+///
+/// The score is the score of the hardest technique needed, in the spirit
+/// of the Sudoku Explainer and HoDoKu scales, plus 0.1 for each further
+/// application of techniques harder than singles (at most 0.5 in total).
+/// Puzzles needing guessing score 10.0.
+///
+/// Scores map to difficulty as follows:
+///
+/// * `Easy`: up to 2.3, singles only.
+/// * `Medium`: up to 3.4, e.g. intersections, naked pairs and X-Wings.
+/// * `Hard`: above 3.4, solvable without guessing.
+/// * `Evil`: requires guessing.
+///
+/// The puzzle is assumed to have a unique solution.
+pub struct Rater {
+    solver: LogicalSolver,
+}
+
+impl Default for Rater {
+    fn default() -> Rater {
+        Rater::new()
+    }
+}
+
+impl Rater {
+    /// Creates new `Rater` using the default technique catalog.
+    pub fn new() -> Rater {
+        Rater::with_solver(LogicalSolver::new())
+    }
+
+    /// Creates new `Rater` using specified solver and its techniques.
+    pub fn with_solver(solver: LogicalSolver) -> Rater {
+        Rater { solver }
+    }
+
+    /// Rates puzzle, or returns `Err(Error)` if it has no solution.
+    pub fn rate(&mut self, board: &SudokuBoard) -> Result<Rating, Error> {
+        let requires_guessing = match self.solver.solve(board) {
+            Ok(_) => false,
+            Err(Error::GuessingRequired) => true,
+            Err(e) => return Err(e),
+        };
+
+        let solver = &self.solver;
+        let scores: Vec<(&'static str, f32)> = solver
+            .deductions()
+            .iter()
+            .map(|deduction| {
+                let score = solver.score_of(deduction.technique).unwrap_or(0.0);
+                (deduction.technique, score)
+            })
+            .collect();
+
+        let hardest =
+            scores
+                .iter()
+                .cloned()
+                .fold(
+                    None,
+                    |hardest: Option<(&'static str, f32)>, step| match hardest {
+                        Some(h) if h.1 >= step.1 => Some(h),
+                        _ => Some(step),
+                    },
+                );
+        let harder_steps = scores.iter().filter(|(_, score)| *score > EASY_MAX).count();
+
+        let score = if requires_guessing {
+            GUESSING_SCORE
+        } else {
+            let steps_score = STEP_SCORE * harder_steps.saturating_sub(1) as f32;
+            hardest.map_or(0.0, |h| h.1) + steps_score.min(MAX_STEPS_SCORE)
+        };
+
+        Ok(Rating {
+            score,
+            hardest: hardest.map(|h| h.0),
+            steps: scores.len() as u32,
+            requires_guessing,
+            difficulty: get_difficulty(score, requires_guessing),
+        })
+    }
+}
+
+fn get_difficulty(score: f32, requires_guessing: bool) -> Difficulty {
+    match score {
+        _ if requires_guessing => Difficulty::Evil,
+        s if s <= EASY_MAX => Difficulty::Easy,
+        s if s <= MEDIUM_MAX => Difficulty::Medium,
+        _ => Difficulty::Hard,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    static EASY: &str =
+        "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+
+    static X_WING: &str =
+        "1.....569492.561.8.561.924...964.8.1.64.1....218.356.4.4.5...169.5.614.2621.....5";
+
+    static AI_ESCARGOT: &str =
+        "1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..";
+
+    #[test]
+    fn when_puzzle_needs_singles_only_then_it_is_easy() {
+        let rating = Rater::new().rate(&EASY.parse().unwrap()).unwrap();
+        assert!(rating.score <= EASY_MAX);
+        assert!(!rating.requires_guessing);
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+    }
+
+    #[test]
+    fn when_puzzle_needs_x_wing_then_score_is_at_least_x_wing() {
+        let rating = Rater::new().rate(&X_WING.parse().unwrap()).unwrap();
+        assert!(rating.score >= 3.2);
+        assert!(rating.difficulty >= Difficulty::Medium);
+        assert!(rating.steps > 0);
+    }
+
+    #[test]
+    fn when_puzzle_needs_guessing_then_it_is_evil() {
+        let rating = Rater::new().rate(&AI_ESCARGOT.parse().unwrap()).unwrap();
+        assert_eq!(rating.score, GUESSING_SCORE);
+        assert!(rating.requires_guessing);
+        assert_eq!(rating.difficulty, Difficulty::Evil);
+    }
+
+    #[test]
+    fn when_puzzle_has_conflicts_then_return_error() {
+        let mut board: SudokuBoard = EASY.parse().unwrap();
+        board.values[0] = 3;
+        assert!(Rater::new().rate(&board).is_err());
+    }
+
+    #[test]
+    fn difficulty_follows_score() {
+        assert_eq!(get_difficulty(1.5, false), Difficulty::Easy);
+        assert_eq!(get_difficulty(2.6, false), Difficulty::Medium);
+        assert_eq!(get_difficulty(3.5, false), Difficulty::Hard);
+        assert_eq!(get_difficulty(1.5, true), Difficulty::Evil);
+    }
+}
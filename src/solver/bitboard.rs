@@ -0,0 +1,484 @@
+extern crate test;
+
+use super::super::board::{Placement, SudokuBoard};
+use super::super::error::Error;
+use super::{Solution, SolutionCount, Solver, Verification};
+
+/// All values 1-9 as bits 0-8.
+const ALL: u16 = 0x1FF;
+
+/// Peers (cells sharing row, column or box) of each cell.
+static PEERS: [[u8; 20]; 81] = build_peers();
+
+/// Cells of each unit: rows, then columns, then boxes.
+static UNITS: [[u8; 9]; 27] = build_units();
+
+const fn build_units() -> [[u8; 9]; 27] {
+    let mut units = [[0; 9]; 27];
+    let mut i = 0;
+    while i < 9 {
+        let mut j = 0;
+        while j < 9 {
+            units[i][j] = (i * 9 + j) as u8;
+            units[9 + i][j] = (j * 9 + i) as u8;
+            units[18 + i][j] = (((i / 3) * 3 + j / 3) * 9 + (i % 3) * 3 + j % 3) as u8;
+            j += 1;
+        }
+        i += 1;
+    }
+    units
+}
+
+const fn build_peers() -> [[u8; 20]; 81] {
+    let mut peers = [[0; 20]; 81];
+    let mut index = 0;
+    while index < 81 {
+        let (row, col) = (index / 9, index % 9);
+        let mut count = 0;
+        let mut other = 0;
+        while other < 81 {
+            let (r, c) = (other / 9, other % 9);
+            let same_box = r / 3 == row / 3 && c / 3 == col / 3;
+            if other != index && (r == row || c == col || same_box) {
+                peers[index][count] = other as u8;
+                count += 1;
+            }
+            other += 1;
+        }
+        index += 1;
+    }
+    peers
+}
+
+/// Returns units of cell, as indices into `UNITS`.
+fn units_of(index: usize) -> [usize; 3] {
+    let (row, col) = (index / 9, index % 9);
+    [row, 9 + col, 18 + (row / 3) * 3 + col / 3]
+}
+
+/// Board state with candidates of each cell as bits.
+#[derive(Clone, Copy)]
+struct State {
+    values: [u8; 81],
+    candidates: [u16; 81],
+    /// Values placed in each unit, in the order of `UNITS`.
+    placed: [u16; 27],
+    /// Order in which cells were filled, for reporting placements.
+    order: [u8; 81],
+    filled: u8,
+}
+
+impl State {
+    /// Creates state from board, or returns `None` if placing
+    /// its values leaves a cell without candidates.
+    fn new(board: &SudokuBoard) -> Option<State> {
+        let mut state = State {
+            values: [0; 81],
+            candidates: [ALL; 81],
+            placed: [0; 27],
+            order: [0; 81],
+            filled: 0,
+        };
+
+        for (index, &val) in board.values.iter().enumerate().filter(|(_, &val)| val > 0) {
+            if !state.place(index, val) {
+                return None;
+            }
+        }
+        Some(state)
+    }
+
+    /// Places value and removes it from peers.
+    /// Returns false if value is not a candidate, or a peer runs out of candidates.
+    fn place(&mut self, index: usize, val: u8) -> bool {
+        let bit = 1 << (val - 1);
+        if self.candidates[index] & bit == 0 {
+            return false;
+        }
+
+        self.values[index] = val;
+        self.candidates[index] = 0;
+        for &unit in &units_of(index) {
+            self.placed[unit] |= bit;
+        }
+        self.order[index] = self.filled;
+        self.filled += 1;
+
+        for &peer in PEERS[index].iter() {
+            let peer = peer as usize;
+            if self.candidates[peer] & bit != 0 {
+                self.candidates[peer] &= !bit;
+                if self.candidates[peer] == 0 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Places all naked and hidden singles until none are left.
+    /// Returns false on contradiction.
+    fn propagate(&mut self) -> bool {
+        loop {
+            let mut progress = false;
+
+            // Naked singles: cells with one candidate
+            for index in 0..81 {
+                let bits = self.candidates[index];
+                if self.values[index] == 0 && bits & (bits - 1) == 0 {
+                    if !self.place(index, bits.trailing_zeros() as u8 + 1) {
+                        return false;
+                    }
+                    progress = true;
+                }
+            }
+
+            // Hidden singles: values with one position in a unit
+            for (unit, cells) in UNITS.iter().enumerate() {
+                let (mut once, mut twice) = (0u16, 0u16);
+                for &index in cells.iter() {
+                    let bits = self.candidates[index as usize];
+                    twice |= once & bits;
+                    once |= bits;
+                }
+
+                if once | self.placed[unit] != ALL {
+                    return false;
+                }
+
+                let mut singles = once & !twice;
+                while singles != 0 {
+                    let bit = singles & singles.wrapping_neg();
+                    singles &= !bit;
+                    let index = cells
+                        .iter()
+                        .map(|&index| index as usize)
+                        .find(|&index| self.candidates[index] & bit != 0);
+                    match index {
+                        Some(index) => {
+                            if !self.place(index, bit.trailing_zeros() as u8 + 1) {
+                                return false;
+                            }
+                            progress = true;
+                        }
+                        // Removed by an earlier placement in this unit
+                        None => return false,
+                    }
+                }
+            }
+
+            if !progress {
+                return true;
+            }
+        }
+    }
+
+    /// Returns empty cell with fewest candidates, if any.
+    fn choose_cell(&self) -> Option<usize> {
+        (0..81)
+            .filter(|&index| self.values[index] == 0)
+            .min_by_key(|&index| self.candidates[index].count_ones())
+    }
+}
+
+/// Solution found by search, with number of branches.
+struct Found {
+    state: State,
+    branches: u32,
+}
+
+impl Found {
+    /// Returns board with solution filled in.
+    fn board_of(&self, board: &SudokuBoard) -> SudokuBoard {
+        let mut solved = board.clone();
+        solved.values = self.state.values;
+        solved
+    }
+}
+
+/// High-performance Sudoku solver using bit masks for candidates.
+///
+/// # Remarks
+///
+/// Candidates of each cell are stored as bits of a `u16`, and the values
+/// placed in each row, column and box as a mask kept up to date with
+/// every placement. Naked and hidden singles are propagated with bit
+/// operations, and when no singles are left the solver branches on the
+/// cell with the fewest candidates, copying the (small) board state for
+/// each branch.
+///
+/// Branches are counted as for `LeastOptionsSolver`: each branch on
+/// the solution path adds the number of alternatives it had.
+/// Iterations are counted as placements, including propagated singles.
+#[derive(Default)]
+pub struct BitboardSolver {
+    found: Vec<Found>,
+    solutions: u32,
+    max_iterations: Option<u32>,
+    iterations: u32,
+}
+
+impl Solver for BitboardSolver {
+    fn verify(&mut self, board: &SudokuBoard) -> Verification {
+        self.max_iterations = None;
+        if self.search(board, 2).is_err() {
+            return Verification::invalid_clues(board);
+        }
+
+        match self.found.as_slice() {
+            [] => Verification::unsolvable(board),
+            [found] => Verification::ValidWithBranches(found.branches),
+            [first, second, ..] => {
+                Verification::multiple_solutions(first.board_of(board), second.board_of(board))
+            }
+        }
+    }
+
+    fn count_solutions(&mut self, board: &SudokuBoard, limit: u32) -> SolutionCount {
+        self.max_iterations = None;
+        match self.search(board, limit) {
+            Ok(()) => SolutionCount::new(self.solutions, limit),
+            Err(_) => SolutionCount::Exactly(0),
+        }
+    }
+
+    fn solve(&mut self, board: &SudokuBoard) -> Result<Solution, Error> {
+        self.try_solve(board, None)
+    }
+
+    fn try_solve(
+        &mut self,
+        board: &SudokuBoard,
+        max_iterations: Option<u32>,
+    ) -> Result<Solution, Error> {
+        self.max_iterations = max_iterations;
+        self.search(board, 1)?;
+
+        if self.found.is_empty() {
+            return Err(Error::NoSolution);
+        }
+        let found = self.found.swap_remove(0);
+
+        let mut filled: Vec<usize> = (0..81).filter(|&index| board.values[index] == 0).collect();
+        filled.sort_by_key(|&index| found.state.order[index]);
+        let placements: Vec<Placement> = filled
+            .iter()
+            .map(|&index| (index / 9, index % 9, found.state.values[index]))
+            .collect();
+
+        Ok(Solution {
+            board: found.board_of(board),
+            placements,
+            branches: found.branches,
+            steps: Vec::new(),
+        })
+    }
+}
+
+impl BitboardSolver {
+    pub fn new() -> BitboardSolver {
+        BitboardSolver {
+            found: Vec::with_capacity(2),
+            solutions: 0,
+            max_iterations: None,
+            iterations: 0,
+        }
+    }
+
+    /// Searches for up to `limit` solutions of board.
+    fn search(&mut self, board: &SudokuBoard, limit: u32) -> Result<(), Error> {
+        self.found.clear();
+        self.solutions = 0;
+        self.iterations = 0;
+
+        if !board.is_valid() {
+            return Err(Error::InvalidBoard);
+        }
+
+        match State::new(board) {
+            Some(state) => self.search_state(state, 0, limit).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Counts solutions and keeps the first two.
+    /// Returns `Ok(true)` when `limit` solutions have been found.
+    fn search_state(&mut self, mut state: State, branches: u32, limit: u32) -> Result<bool, Error> {
+        let filled = state.filled;
+        if !state.propagate() {
+            return Ok(false);
+        }
+        self.add_iterations(u32::from(state.filled - filled))?;
+
+        let index = match state.choose_cell() {
+            None => {
+                self.solutions += 1;
+                if self.found.len() < 2 {
+                    self.found.push(Found { state, branches });
+                }
+                return Ok(self.solutions >= limit);
+            }
+            Some(index) => index,
+        };
+
+        let options = state.candidates[index];
+        let alts = options.count_ones() - 1;
+        let mut remaining = options;
+        while remaining != 0 {
+            let bit = remaining & remaining.wrapping_neg();
+            remaining &= !bit;
+
+            let mut next = state;
+            self.add_iterations(1)?;
+            if next.place(index, bit.trailing_zeros() as u8 + 1)
+                && self.search_state(next, branches + alts, limit)?
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn add_iterations(&mut self, placements: u32) -> Result<(), Error> {
+        self.iterations += placements;
+        match self.max_iterations {
+            Some(max) if self.iterations > max => Err(Error::IterationLimit(max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::super::board::SudokuBoard;
+    use super::super::least_options::LeastOptionsSolver;
+    use super::test::Bencher;
+    use super::{BitboardSolver, Error, SolutionCount, Solver, Verification};
+
+    static SUPER_HARD: &str =
+        "...376..8.....1..6.2.......7.4...93.1.8..2...26..9...4.....3.71......86....96....";
+
+    static AGAINST_BRUTE_FORCE: &str =
+        "..............3.85..1.2.......5.7.....4...1...9.......5......73..2.1........4...9";
+
+    static REFLECTION_SYMMETRY: &str =
+        "..........9..1..3...6.2.7.....3.4...21.....98...........25.64...8.....1..........";
+
+    #[test]
+    fn verify_super_hard() {
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+
+        let result = BitboardSolver::new().verify(&board);
+        assert!(matches!(result, Verification::ValidWithBranches(_)));
+    }
+
+    #[test]
+    fn verify_reflection_symmetry() {
+        let board: SudokuBoard = REFLECTION_SYMMETRY.parse().unwrap();
+
+        let result = BitboardSolver::new().verify(&board);
+        assert!(matches!(result, Verification::ValidWithBranches(_)));
+    }
+
+    #[test]
+    fn solve_super_hard() {
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+
+        let solution = BitboardSolver::new()
+            .solve(&board)
+            .expect("Expected success");
+        assert!(solution.board.is_filled() && solution.board.is_valid());
+        assert_eq!(solution.placements.len(), 81 - 25);
+    }
+
+    #[test]
+    fn solve_against_brute_force() {
+        let board: SudokuBoard = AGAINST_BRUTE_FORCE.parse().unwrap();
+
+        let solution = BitboardSolver::new()
+            .solve(&board)
+            .expect("Expected success");
+        let expected = LeastOptionsSolver::new().solve(&board).unwrap();
+        assert_eq!(solution.board.to_line(), expected.board.to_line());
+    }
+
+    #[test]
+    fn verify_fails_for_board_with_many_solutions() {
+        let board = SudokuBoard::with_clues(&[(0, 0, 1), (4, 4, 5)]);
+
+        match BitboardSolver::new().verify(&board) {
+            Verification::MultipleSolutions {
+                first,
+                second,
+                differences,
+            } => {
+                assert!(first.is_filled() && second.is_filled());
+                assert_ne!(first, second);
+                assert!(!differences.is_empty());
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn solve_fails_for_board_without_solution() {
+        let board: SudokuBoard =
+            ".23456789.........1.......................................................1......"
+                .parse()
+                .unwrap();
+
+        match BitboardSolver::new().solve(&board) {
+            Err(Error::NoSolution) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn solve_fails_for_board_with_invalid_clues() {
+        let mut board: SudokuBoard = SUPER_HARD.parse().unwrap();
+        board.values[4] = 3;
+
+        match BitboardSolver::new().solve(&board) {
+            Err(Error::InvalidBoard) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn try_solve_fails_when_iterations_exceeded() {
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+
+        match BitboardSolver::new().try_solve(&board, Some(5)) {
+            Err(Error::IterationLimit(5)) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn count_solutions_up_to_limit() {
+        let mut solver = BitboardSolver::new();
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+        assert_eq!(
+            solver.count_solutions(&board, 10),
+            SolutionCount::Exactly(1)
+        );
+
+        let empty = SudokuBoard::with_clues(&[]);
+        assert_eq!(
+            solver.count_solutions(&empty, 500),
+            SolutionCount::AtLeast(500)
+        );
+    }
+
+    #[bench]
+    fn bench_verify_super_hard(b: &mut Bencher) {
+        b.iter(verify_super_hard);
+    }
+
+    #[bench]
+    fn bench_super_hard(b: &mut Bencher) {
+        b.iter(solve_super_hard);
+    }
+}
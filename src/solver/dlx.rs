@@ -0,0 +1,493 @@
+extern crate test;
+
+use super::super::board::{Placement, SudokuBoard};
+use super::super::error::Error;
+use super::{Solution, SolutionCount, Solver, Verification};
+
+/// Number of exact cover constraints: each cell has one value,
+/// and each row, column and box has each value once.
+const COLUMNS: usize = 4 * 81;
+/// Number of candidate placements, one for each value of each cell.
+const ROWS: usize = 9 * 81;
+/// Index of root header node.
+const ROOT: usize = 0;
+
+/// Dancing links for the Sudoku exact cover matrix.
+///
+/// Nodes `1..=COLUMNS` are column headers, followed by four nodes
+/// for each matrix row.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl Links {
+    fn new() -> Links {
+        let nodes = 1 + COLUMNS + 4 * ROWS;
+        let mut links = Links {
+            left: vec![0; nodes],
+            right: vec![0; nodes],
+            up: vec![0; nodes],
+            down: vec![0; nodes],
+            column: vec![0; nodes],
+            row: vec![0; nodes],
+            size: vec![0; COLUMNS + 1],
+        };
+
+        for header in 0..=COLUMNS {
+            links.left[header] = if header == 0 { COLUMNS } else { header - 1 };
+            links.right[header] = if header == COLUMNS { 0 } else { header + 1 };
+            links.up[header] = header;
+            links.down[header] = header;
+            links.column[header] = header;
+        }
+
+        let mut node = COLUMNS + 1;
+        for matrix_row in 0..ROWS {
+            let first = node;
+            for (i, &header) in constraints(matrix_row).iter().enumerate() {
+                links.column[node] = header;
+                links.row[node] = matrix_row;
+                links.up[node] = links.up[header];
+                links.down[node] = header;
+                links.down[links.up[header]] = node;
+                links.up[header] = node;
+                links.size[header] += 1;
+                links.left[node] = if i == 0 { first + 3 } else { node - 1 };
+                links.right[node] = if i == 3 { first } else { node + 1 };
+                node += 1;
+            }
+        }
+
+        links
+    }
+
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+
+        let mut i = self.down[header];
+        while i != header {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+        while i != header {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    /// Returns node of matrix row for placing `val` at board `index`.
+    fn node_of(index: usize, val: u8) -> usize {
+        1 + COLUMNS + 4 * (index * 9 + (val - 1) as usize)
+    }
+
+    /// Returns the uncovered column with the fewest nodes, if any.
+    fn choose_column(&self) -> Option<usize> {
+        let mut best = None;
+        let mut header = self.right[ROOT];
+        while header != ROOT {
+            match best {
+                Some(b) if self.size[b] <= self.size[header] => (),
+                _ => best = Some(header),
+            }
+            header = self.right[header];
+        }
+        best
+    }
+}
+
+/// Returns the four constraint columns (1-based) satisfied by a matrix row.
+fn constraints(matrix_row: usize) -> [usize; 4] {
+    let (index, val) = (matrix_row / 9, matrix_row % 9);
+    let (row, col) = (index / 9, index % 9);
+    let the_box = (row / 3) * 3 + col / 3;
+    [
+        1 + index,
+        1 + 81 + row * 9 + val,
+        1 + 162 + col * 9 + val,
+        1 + 243 + the_box * 9 + val,
+    ]
+}
+
+fn placement_of(matrix_row: usize) -> Placement {
+    let index = matrix_row / 9;
+    (index / 9, index % 9, (matrix_row % 9 + 1) as u8)
+}
+
+/// Solution found by search: chosen matrix rows and number of branches.
+struct Found {
+    rows: Vec<usize>,
+    branches: u32,
+}
+
+impl Found {
+    /// Returns board with solution filled in.
+    fn board_of(&self, board: &SudokuBoard) -> SudokuBoard {
+        let mut solved = board.clone();
+        for &node_row in &self.rows {
+            let (row, col, val) = placement_of(node_row);
+            solved.values[row * 9 + col] = val;
+        }
+        solved
+    }
+}
+
+/// Sudoku solver using Knuth's Algorithm X with dancing links.
+///
+/// # Remarks
+///
+/// The puzzle is solved as an exact cover problem with 324 constraints:
+/// each cell holds one value, and each row, column and box holds each
+/// value once. The search always branches on the constraint with the
+/// fewest remaining options.
+///
+/// Each placement on the solution path adds the number of alternatives
+/// it had to the branches. As the search branches on constraints rather
+/// than cells, counts differ from those of `LeastOptionsSolver`.
+pub struct DlxSolver {
+    links: Links,
+    path: Vec<(usize, u32)>,
+    found: Vec<Found>,
+    solutions: u32,
+    max_iterations: Option<u32>,
+    iterations: u32,
+}
+
+impl Default for DlxSolver {
+    fn default() -> DlxSolver {
+        DlxSolver::new()
+    }
+}
+
+impl Solver for DlxSolver {
+    fn verify(&mut self, board: &SudokuBoard) -> Verification {
+        self.max_iterations = None;
+        if self.search(board, 2).is_err() {
+            return Verification::invalid_clues(board);
+        }
+
+        match self.found.as_slice() {
+            [] => Verification::unsolvable(board),
+            [found] => Verification::ValidWithBranches(found.branches),
+            [first, second, ..] => {
+                Verification::multiple_solutions(first.board_of(board), second.board_of(board))
+            }
+        }
+    }
+
+    fn count_solutions(&mut self, board: &SudokuBoard, limit: u32) -> SolutionCount {
+        self.max_iterations = None;
+        match self.search(board, limit) {
+            Ok(()) => SolutionCount::new(self.solutions, limit),
+            Err(_) => SolutionCount::Exactly(0),
+        }
+    }
+
+    fn solve(&mut self, board: &SudokuBoard) -> Result<Solution, Error> {
+        self.try_solve(board, None)
+    }
+
+    fn try_solve(
+        &mut self,
+        board: &SudokuBoard,
+        max_iterations: Option<u32>,
+    ) -> Result<Solution, Error> {
+        self.max_iterations = max_iterations;
+        self.search(board, 1)?;
+
+        if self.found.is_empty() {
+            return Err(Error::NoSolution);
+        }
+        let found = self.found.swap_remove(0);
+        let placements: Vec<Placement> = found.rows.iter().map(|&row| placement_of(row)).collect();
+
+        Ok(Solution {
+            board: found.board_of(board),
+            placements,
+            branches: found.branches,
+            steps: Vec::new(),
+        })
+    }
+}
+
+impl DlxSolver {
+    pub fn new() -> DlxSolver {
+        DlxSolver {
+            links: Links::new(),
+            path: Vec::with_capacity(81),
+            found: Vec::with_capacity(2),
+            solutions: 0,
+            max_iterations: None,
+            iterations: 0,
+        }
+    }
+
+    /// Searches for up to `limit` solutions of board.
+    fn search(&mut self, board: &SudokuBoard, limit: u32) -> Result<(), Error> {
+        if !board.is_valid() {
+            return Err(Error::InvalidBoard);
+        }
+
+        self.path.clear();
+        self.found.clear();
+        self.solutions = 0;
+        self.iterations = 0;
+
+        // Values on board are fixed, select their rows up front
+        let mut covered = Vec::with_capacity(4 * 81);
+        for (index, &val) in board.values.iter().enumerate().filter(|(_, &val)| val > 0) {
+            let node = Links::node_of(index, val);
+            let mut j = node;
+            loop {
+                covered.push(self.links.column[j]);
+                self.links.cover(self.links.column[j]);
+                j = self.links.right[j];
+                if j == node {
+                    break;
+                }
+            }
+        }
+
+        match self.search_level(limit) {
+            Ok(_) => {
+                // Restore links for next search
+                for &header in covered.iter().rev() {
+                    self.links.uncover(header);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                // Search was interrupted, links cannot be restored
+                self.links = Links::new();
+                Err(e)
+            }
+        }
+    }
+
+    /// Counts solutions and keeps the first two.
+    /// Returns `Ok(true)` when `limit` solutions have been found.
+    fn search_level(&mut self, limit: u32) -> Result<bool, Error> {
+        let header = match self.links.choose_column() {
+            None => {
+                self.solutions += 1;
+                if self.found.len() < 2 {
+                    self.found.push(Found {
+                        rows: self.path.iter().map(|&(row, _)| row).collect(),
+                        branches: self.path.iter().map(|&(_, alts)| alts).sum(),
+                    });
+                }
+                return Ok(self.solutions >= limit);
+            }
+            Some(header) => header,
+        };
+
+        let options = self.links.size[header];
+        if options == 0 {
+            return Ok(false);
+        }
+
+        self.links.cover(header);
+        let mut done = false;
+        let mut i = self.links.down[header];
+        while i != header && !done {
+            self.inc_placement_counter()?;
+            self.path.push((self.links.row[i], (options - 1) as u32));
+
+            let mut j = self.links.right[i];
+            while j != i {
+                self.links.cover(self.links.column[j]);
+                j = self.links.right[j];
+            }
+
+            done = self.search_level(limit)?;
+
+            let mut j = self.links.left[i];
+            while j != i {
+                self.links.uncover(self.links.column[j]);
+                j = self.links.left[j];
+            }
+
+            self.path.pop();
+            i = self.links.down[i];
+        }
+        self.links.uncover(header);
+
+        Ok(done)
+    }
+
+    fn inc_placement_counter(&mut self) -> Result<(), Error> {
+        self.iterations += 1;
+        match self.max_iterations {
+            Some(max) if self.iterations > max => Err(Error::IterationLimit(max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::super::board::SudokuBoard;
+    use super::super::least_options::LeastOptionsSolver;
+    use super::test::Bencher;
+    use super::{DlxSolver, Error, SolutionCount, Solver, Verification};
+
+    static SUPER_HARD: &str =
+        "...376..8.....1..6.2.......7.4...93.1.8..2...26..9...4.....3.71......86....96....";
+
+    static AGAINST_BRUTE_FORCE: &str =
+        "..............3.85..1.2.......5.7.....4...1...9.......5......73..2.1........4...9";
+
+    static REFLECTION_SYMMETRY: &str =
+        "..........9..1..3...6.2.7.....3.4...21.....98...........25.64...8.....1..........";
+
+    #[test]
+    fn verify_super_hard() {
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+
+        let result = DlxSolver::new().verify(&board);
+        assert!(matches!(result, Verification::ValidWithBranches(_)));
+    }
+
+    #[test]
+    fn verify_reflection_symmetry() {
+        let board: SudokuBoard = REFLECTION_SYMMETRY.parse().unwrap();
+
+        let result = DlxSolver::new().verify(&board);
+        assert!(matches!(result, Verification::ValidWithBranches(_)));
+    }
+
+    #[test]
+    fn solve_super_hard() {
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+
+        let solution = DlxSolver::new().solve(&board).expect("Expected success");
+        assert!(solution.board.is_filled() && solution.board.is_valid());
+    }
+
+    #[test]
+    fn solve_against_brute_force() {
+        let board: SudokuBoard = AGAINST_BRUTE_FORCE.parse().unwrap();
+
+        let solution = DlxSolver::new().solve(&board).expect("Expected success");
+        let expected = LeastOptionsSolver::new().solve(&board).unwrap();
+        assert_eq!(solution.board.to_line(), expected.board.to_line());
+    }
+
+    #[test]
+    fn verify_fails_for_board_with_many_solutions() {
+        let board = SudokuBoard::with_clues(&[(0, 0, 1), (4, 4, 5)]);
+
+        match DlxSolver::new().verify(&board) {
+            Verification::MultipleSolutions {
+                first,
+                second,
+                differences,
+            } => {
+                assert!(first.is_filled() && second.is_filled());
+                assert_ne!(first, second);
+                assert!(!differences.is_empty());
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn solve_fails_for_board_without_solution() {
+        // Value 1 cannot be placed in top left cell, which has no other options
+        let board: SudokuBoard =
+            ".23456789.........1.......................................................1......"
+                .parse()
+                .unwrap();
+
+        match DlxSolver::new().solve(&board) {
+            Err(Error::NoSolution) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn solve_fails_for_board_with_invalid_clues() {
+        let mut board: SudokuBoard = SUPER_HARD.parse().unwrap();
+        board.values[4] = 3;
+
+        match DlxSolver::new().solve(&board) {
+            Err(Error::InvalidBoard) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn solver_can_be_reused_after_iteration_limit() {
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+        let mut solver = DlxSolver::new();
+
+        match solver.try_solve(&board, Some(3)) {
+            Err(Error::IterationLimit(3)) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        let first = solver.solve(&board).unwrap();
+        let second = solver.solve(&board).unwrap();
+        assert_eq!(first.board.to_line(), second.board.to_line());
+        assert!(matches!(
+            solver.verify(&board),
+            Verification::ValidWithBranches(_)
+        ));
+    }
+
+    #[test]
+    fn count_solutions_up_to_limit() {
+        let mut solver = DlxSolver::new();
+        let board: SudokuBoard = SUPER_HARD.parse().unwrap();
+        assert_eq!(
+            solver.count_solutions(&board, 10),
+            SolutionCount::Exactly(1)
+        );
+
+        let empty = SudokuBoard::with_clues(&[]);
+        assert_eq!(
+            solver.count_solutions(&empty, 500),
+            SolutionCount::AtLeast(500)
+        );
+    }
+
+    #[bench]
+    fn bench_verify_super_hard(b: &mut Bencher) {
+        b.iter(verify_super_hard);
+    }
+
+    #[bench]
+    fn bench_super_hard(b: &mut Bencher) {
+        b.iter(solve_super_hard);
+    }
+
+    #[bench]
+    fn bench_against_brute_force(b: &mut Bencher) {
+        b.iter(solve_against_brute_force);
+    }
+}
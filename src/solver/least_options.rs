@@ -1,10 +1,11 @@
 extern crate test;
 
 use super::super::board::{Placement, SudokuBoard};
+use super::super::error::Error;
 use super::super::group::{add, sub, Group};
 use super::{Solution, Solver, Verification};
 
-#[cfg_attr(rustfmt, rustfmt_skip)]
+#[rustfmt::skip]
 static BOX_BY_INDEX: [usize; 81] =
     [
         0, 0, 0, 1, 1, 1, 2, 2, 2,
@@ -18,7 +19,7 @@ static BOX_BY_INDEX: [usize; 81] =
         6, 6, 6, 7, 7, 7, 8, 8, 8,
     ];
 
-#[cfg_attr(rustfmt, rustfmt_skip)]
+#[rustfmt::skip]
 static BOX_BY_COORDS: [[usize; 9]; 9] =
     [
         [ 0, 0, 0, 1, 1, 1, 2, 2, 2, ],
@@ -32,11 +33,11 @@ static BOX_BY_COORDS: [[usize; 9]; 9] =
         [ 6, 6, 6, 7, 7, 7, 8, 8, 8, ],
     ];
 
-#[cfg_attr(rustfmt, rustfmt_skip)]
+#[rustfmt::skip]
 static BOX_TO_COORDS: [[(usize, usize); 9]; 9] =
     [
         [ (0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2), ],
-        [ (0, 3), (0, 4), (0, 5), (1, 3), (1, 4), (0, 5), (2, 3), (2, 4), (2, 5), ],
+        [ (0, 3), (0, 4), (0, 5), (1, 3), (1, 4), (1, 5), (2, 3), (2, 4), (2, 5), ],
         [ (0, 6), (0, 7), (0, 8), (1, 6), (1, 7), (1, 8), (2, 6), (2, 7), (2, 8), ],
         [ (3, 0), (3, 1), (3, 2), (4, 0), (4, 1), (4, 2), (5, 0), (5, 1), (5, 2), ],
         [ (3, 3), (3, 4), (3, 5), (4, 3), (4, 4), (4, 5), (5, 3), (5, 4), (5, 5), ],
@@ -73,6 +74,38 @@ pub struct LeastOptionsSolver {
     iterations: u32,
 }
 
+/// Iterator over all solutions of a board.
+///
+/// Created by [`LeastOptionsSolver::solutions`](struct.LeastOptionsSolver.html#method.solutions).
+pub struct Solutions {
+    solver: LeastOptionsSolver,
+    board: SudokuBoard,
+    done: bool,
+}
+
+impl Iterator for Solutions {
+    type Item = SudokuBoard;
+
+    fn next(&mut self) -> Option<SudokuBoard> {
+        if self.done {
+            return None;
+        }
+
+        // Backtracks from previous solution when one was found
+        match self.solver.find_solution(&mut self.board) {
+            Ok(()) => {
+                // Nothing to backtrack when board was already filled
+                self.done = self.solver.solution.is_empty();
+                Some(self.board.clone())
+            }
+            Err(_) => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
 impl Solver for LeastOptionsSolver {
     fn verify(&mut self, board: &SudokuBoard) -> Verification {
         let mut clone = board.clone();
@@ -83,17 +116,19 @@ impl Solver for LeastOptionsSolver {
         match self.find_solution(&mut clone) {
             Ok(_) => {
                 let branches = self.branches();
+                let first = clone.clone();
                 if self.find_solution(&mut clone).is_err() && self.solution.is_empty() {
                     Verification::ValidWithBranches(branches)
                 } else {
-                    Verification::NotValid
+                    Verification::multiple_solutions(first, clone)
                 }
             }
-            Err(_) => Verification::NotValid,
+            Err(Error::InvalidBoard) => Verification::invalid_clues(board),
+            Err(_) => Verification::unsolvable(board),
         }
     }
 
-    fn solve(&mut self, board: &SudokuBoard) -> Result<Solution, String> {
+    fn solve(&mut self, board: &SudokuBoard) -> Result<Solution, Error> {
         self.try_solve(board, None)
     }
 
@@ -101,7 +136,7 @@ impl Solver for LeastOptionsSolver {
         &mut self,
         board: &SudokuBoard,
         max_iterations: Option<u32>,
-    ) -> Result<Solution, String> {
+    ) -> Result<Solution, Error> {
         self.solution.clear();
         self.max_iterations = max_iterations;
         let mut solve_board = board.clone();
@@ -116,6 +151,7 @@ impl Solver for LeastOptionsSolver {
             board: solve_board,
             placements: result,
             branches: branches.iter().sum(),
+            steps: Vec::new(),
         })
     }
 }
@@ -129,21 +165,35 @@ impl LeastOptionsSolver {
         }
     }
 
+    /// Returns iterator lazily enumerating every solution of board.
+    ///
+    /// Yields nothing when the board has conflicting clues or no solution.
+    pub fn solutions(mut self, board: &SudokuBoard) -> Solutions {
+        self.solution.clear();
+        self.max_iterations = None;
+        Solutions {
+            solver: self,
+            board: board.clone(),
+            done: false,
+        }
+    }
+
     fn branches(&self) -> u32 {
         self.solution.iter().map(|step| step.branches).sum()
     }
 
-    fn find_solution(&mut self, board: &mut SudokuBoard) -> Result<(), String> {
+    #[allow(clippy::needless_range_loop)]
+    fn find_solution(&mut self, board: &mut SudokuBoard) -> Result<(), Error> {
         self.iterations = 0;
         let backtrack = !self.solution.is_empty();
 
         if !backtrack && !board.is_valid() {
-            return Err(String::from("Cannot solve invalid board"));
+            return Err(Error::InvalidBoard);
         }
 
         // Pre-calculate available placements for all positions
         // Pre-calculate number of options for all groups and values
-        let mut opts = AvailableOptions::calculate_options(&board);
+        let mut opts = AvailableOptions::calculate_options(board);
 
         while !board.is_filled() || (backtrack && self.iterations == 0) {
             let mut found_placements = false;
@@ -264,7 +314,7 @@ impl LeastOptionsSolver {
                 loop {
                     let mut found_alt = false;
                     match self.solution.pop() {
-                        None => return Err(String::from("No solution found")),
+                        None => return Err(Error::NoSolution),
                         Some(mut step) => {
                             let (row, col, _) = step.placement;
                             board.place((row, col, 0))?;
@@ -356,7 +406,7 @@ impl LeastOptionsSolver {
         alts: Vec<Placement>,
         branches: u32,
         opts: &mut AvailableOptions,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         self.solution.push(SolutionStep {
             placement: val,
             alts,
@@ -369,10 +419,10 @@ impl LeastOptionsSolver {
         Ok(())
     }
 
-    fn inc_placement_counter(&mut self) -> Result<(), String> {
+    fn inc_placement_counter(&mut self) -> Result<(), Error> {
         self.iterations += 1;
         match self.max_iterations {
-            Some(max) if self.iterations > max => Err(format!("Max placements attempted: {}", max)),
+            Some(max) if self.iterations > max => Err(Error::IterationLimit(max)),
             _ => Ok(()),
         }
     }
@@ -386,6 +436,7 @@ struct AvailableOptions {
 }
 
 impl AvailableOptions {
+    #[allow(clippy::needless_range_loop)]
     pub fn calculate_options(board: &SudokuBoard) -> AvailableOptions {
         let mut result = AvailableOptions {
             placements: [[0; 9]; 81],
@@ -441,9 +492,13 @@ impl AvailableOptions {
 mod tests {
 
     use super::super::super::board::{Placement, SudokuBoard};
+    use super::super::super::error::Error;
+    use super::super::dlx::DlxSolver;
+    use super::super::SolutionCount;
     use super::test::Bencher;
     use super::LeastOptionsSolver;
     use super::{Solver, Verification};
+    use super::{BOX_BY_COORDS, BOX_TO_COORDS};
 
     static SUPER_HARD: [Placement; 25] = [
         (0, 3, 3),
@@ -555,10 +610,10 @@ mod tests {
 
     #[test]
     fn solve_super_hard() {
-        let mut board = SudokuBoard::with_clues(&SUPER_HARD);
+        let board = SudokuBoard::with_clues(&SUPER_HARD);
 
         LeastOptionsSolver::new()
-            .solve(&mut board)
+            .solve(&board)
             .expect("Expected success");
     }
 
@@ -567,60 +622,125 @@ mod tests {
         let mut board = SudokuBoard::with_clues(&SUPER_HARD);
         board.values[4] = 3;
 
-        LeastOptionsSolver::new()
-            .find_solution(&mut board)
-            .expect_err("Expected error");
+        match LeastOptionsSolver::new().find_solution(&mut board) {
+            Err(Error::InvalidBoard) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn try_solve_fails_when_iterations_exceeded() {
+        let board = SudokuBoard::with_clues(&SUPER_HARD);
+
+        match LeastOptionsSolver::new().try_solve(&board, Some(5)) {
+            Err(Error::IterationLimit(5)) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn box_to_coords_lists_every_cell_of_each_box_once() {
+        let mut seen = [false; 81];
+        for (a_box, cells) in BOX_TO_COORDS.iter().enumerate() {
+            for &(row, col) in cells {
+                assert_eq!(BOX_BY_COORDS[row][col], a_box);
+                assert!(!seen[row * 9 + col], "({}, {}) listed twice", row, col);
+                seen[row * 9 + col] = true;
+            }
+        }
     }
 
     #[test]
     fn solve_evil() {
-        let mut board = SudokuBoard::with_clues(&EVIL);
+        let board = SudokuBoard::with_clues(&EVIL);
 
         let _result = LeastOptionsSolver::new()
-            .solve(&mut board)
+            .solve(&board)
             .expect("Expected success");
     }
 
     #[test]
     fn solve_reflection_symmetry() {
-        let mut board = SudokuBoard::with_clues(&REFLECTION_SYMMETRY);
+        let board = SudokuBoard::with_clues(&REFLECTION_SYMMETRY);
 
         let _result = LeastOptionsSolver::new()
-            .solve(&mut board)
+            .solve(&board)
             .expect("Expected success");
     }
 
     #[test]
     fn solve_against_brute_force() {
-        let mut board = SudokuBoard::with_clues(&AGAINST_BRUTE_FORCE);
+        let board = SudokuBoard::with_clues(&AGAINST_BRUTE_FORCE);
 
         let _result = LeastOptionsSolver::new()
-            .solve(&mut board)
+            .solve(&board)
             .expect("Expected success");
     }
 
+    #[test]
+    fn solutions_enumerates_every_completion() {
+        let board: SudokuBoard =
+            "..3456789456789123789123456..4365897365897214897214365531642978642978531978531642"
+                .parse()
+                .unwrap();
+
+        let solutions: Vec<SudokuBoard> = LeastOptionsSolver::new().solutions(&board).collect();
+        assert_eq!(solutions.len(), 2);
+        assert_ne!(solutions[0].to_line(), solutions[1].to_line());
+        assert!(solutions.iter().all(|s| s.is_filled() && s.is_valid()));
+    }
+
+    #[test]
+    fn solutions_matches_solution_count() {
+        let mut board = SudokuBoard::with_clues(&SUPER_HARD);
+        for &index in &[3, 4, 19] {
+            board.values[index] = 0;
+            board.clues[index] = false;
+        }
+
+        let count = LeastOptionsSolver::new().solutions(&board).count() as u32;
+        assert_eq!(
+            DlxSolver::new().count_solutions(&board, 10_000),
+            SolutionCount::Exactly(count)
+        );
+    }
+
+    #[test]
+    fn solutions_of_filled_board_yields_board() {
+        let board = LeastOptionsSolver::new()
+            .solve(&SudokuBoard::with_clues(&SUPER_HARD))
+            .unwrap()
+            .board;
+
+        let solutions: Vec<String> = LeastOptionsSolver::new()
+            .solutions(&board)
+            .map(|solution| solution.to_line())
+            .collect();
+        assert_eq!(solutions, vec![board.to_line()]);
+    }
+
     #[bench]
     fn bench_verify_super_hard(b: &mut Bencher) {
-        b.iter(|| verify_super_hard());
+        b.iter(verify_super_hard);
     }
 
     #[bench]
     fn bench_super_hard(b: &mut Bencher) {
-        b.iter(|| solve_super_hard());
+        b.iter(solve_super_hard);
     }
 
     #[bench]
     fn bench_evil(b: &mut Bencher) {
-        b.iter(|| solve_evil());
+        b.iter(solve_evil);
     }
 
     #[bench]
     fn bench_reflection_symmetry(b: &mut Bencher) {
-        b.iter(|| solve_reflection_symmetry());
+        b.iter(solve_reflection_symmetry);
     }
 
     #[bench]
     fn bench_against_brute_force(b: &mut Bencher) {
-        b.iter(|| solve_against_brute_force());
+        b.iter(solve_against_brute_force);
     }
 }
@@ -0,0 +1,210 @@
+use super::super::super::board::{are_peers, SudokuBoard, Unit};
+use super::super::Contradiction;
+
+/// Bitmask with bit `val - 1` set for each candidate value 1-9.
+pub type Mask = u16;
+
+const ALL: Mask = 0x1FF;
+
+/// Returns mask with only `val` set.
+pub fn mask_of(val: u8) -> Mask {
+    1 << (val - 1)
+}
+
+/// Returns values 1-9 set in mask, in ascending order.
+pub fn values_of(mask: Mask) -> impl Iterator<Item = u8> {
+    (1..10).filter(move |&val| mask & mask_of(val) != 0)
+}
+
+/// Candidate grid for logical solving.
+///
+/// Keeps the value of every cell and, for empty cells,
+/// the values that can still be placed there.
+#[derive(Clone, Debug)]
+pub struct Candidates {
+    values: [u8; 81],
+    masks: [Mask; 81],
+}
+
+impl Candidates {
+    /// Calculates candidates for all empty cells of board.
+    pub fn new(board: &SudokuBoard) -> Candidates {
+        let mut result = Candidates {
+            values: board.values,
+            masks: [0; 81],
+        };
+
+        for index in 0..81 {
+            let allowed = board.get_allowed_vals(index / 9, index % 9);
+            result.masks[index] = allowed
+                .iter()
+                .enumerate()
+                .filter(|(_, &allowed)| allowed == 1)
+                .fold(0, |mask, (val, _)| mask | (1 << val));
+        }
+
+        result
+    }
+
+    /// Creates grid with no values and all candidates in every cell.
+    pub fn empty() -> Candidates {
+        Candidates {
+            values: [0; 81],
+            masks: [ALL; 81],
+        }
+    }
+
+    /// Returns value of cell, or 0 if empty.
+    pub fn value(&self, index: usize) -> u8 {
+        self.values[index]
+    }
+
+    /// Returns candidates of cell. Cells with value have no candidates.
+    pub fn mask(&self, index: usize) -> Mask {
+        self.masks[index]
+    }
+
+    /// Returns true if `val` is a candidate of cell.
+    pub fn has(&self, index: usize, val: u8) -> bool {
+        self.masks[index] & mask_of(val) != 0
+    }
+
+    /// Returns number of candidates of cell.
+    pub fn count(&self, index: usize) -> u32 {
+        self.masks[index].count_ones()
+    }
+
+    /// Returns cells of unit that have `val` as candidate.
+    pub fn positions(&self, unit: Unit, val: u8) -> Vec<usize> {
+        unit.cells()
+            .iter()
+            .cloned()
+            .filter(|&index| self.has(index, val))
+            .collect()
+    }
+
+    /// Places value in cell and removes it from candidates of all peers.
+    pub fn place(&mut self, index: usize, val: u8) {
+        self.values[index] = val;
+        self.masks[index] = 0;
+        for peer in 0..81 {
+            if peer != index && are_peers(index, peer) {
+                self.masks[peer] &= !mask_of(val);
+            }
+        }
+    }
+
+    /// Removes `val` from candidates of cell.
+    pub fn eliminate(&mut self, index: usize, val: u8) {
+        self.masks[index] &= !mask_of(val);
+    }
+
+    /// Returns true if all cells have a value.
+    pub fn is_solved(&self) -> bool {
+        self.values.iter().all(|&val| val > 0)
+    }
+
+    /// Returns true if an empty cell has no candidates, or a unit
+    /// is missing a value that cannot be placed anywhere in it.
+    pub fn has_contradiction(&self) -> bool {
+        self.contradiction().is_some()
+    }
+
+    /// Finds first empty cell with no candidates, or else first unit
+    /// missing a value that cannot be placed anywhere in it.
+    pub fn contradiction(&self) -> Option<Contradiction> {
+        let empty_cell = (0..81).find(|&index| self.values[index] == 0 && self.masks[index] == 0);
+        if let Some(index) = empty_cell {
+            return Some(Contradiction::NoCandidates {
+                row: index / 9,
+                col: index % 9,
+            });
+        }
+
+        Unit::all().find_map(|unit| {
+            let cells = unit.cells();
+            let placed = cells
+                .iter()
+                .filter(|&&index| self.values[index] > 0)
+                .fold(0, |mask, &index| mask | mask_of(self.values[index]));
+            let available = cells
+                .iter()
+                .fold(0, |mask, &index| mask | self.masks[index]);
+            values_of(ALL & !(placed | available))
+                .next()
+                .map(|value| Contradiction::NoPlacement { unit, value })
+        })
+    }
+
+    /// Copies values into board, keeping its clues.
+    pub fn apply_to(&self, board: &mut SudokuBoard) {
+        board.values = self.values;
+    }
+}
+
+/// Returns all combinations of `k` items, in lexicographic order.
+pub fn combinations(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if items.len() < k {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    for (i, &first) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, first);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn when_calculating_candidates_then_peer_values_are_excluded() {
+        let board = SudokuBoard::with_clues(&[(0, 8, 1), (8, 0, 2), (1, 1, 3)]);
+        let grid = Candidates::new(&board);
+        assert_eq!(grid.mask(0), ALL & !0b111);
+        assert_eq!(grid.mask(8), 0);
+        assert_eq!(grid.value(8), 1);
+    }
+
+    #[test]
+    fn when_placing_value_then_remove_it_from_peers() {
+        let mut grid = Candidates::empty();
+        grid.place(40, 5);
+        assert!(!grid.has(4, 5));
+        assert!(!grid.has(36, 5));
+        assert!(!grid.has(30, 5));
+        assert!(grid.has(0, 5));
+        assert_eq!(grid.mask(40), 0);
+    }
+
+    #[test]
+    fn when_cell_has_no_candidates_then_has_contradiction() {
+        let mut grid = Candidates::empty();
+        assert!(!grid.has_contradiction());
+        for val in 1..10 {
+            grid.eliminate(0, val);
+        }
+        assert!(grid.has_contradiction());
+        assert_eq!(
+            grid.contradiction(),
+            Some(Contradiction::NoCandidates { row: 0, col: 0 })
+        );
+    }
+
+    #[test]
+    fn when_choosing_combinations_then_return_all_subsets() {
+        let result = combinations(&[1, 2, 3, 4], 2);
+        assert_eq!(result.len(), 6);
+        assert_eq!(result[0], vec![1, 2]);
+        assert_eq!(result[5], vec![3, 4]);
+    }
+}
@@ -0,0 +1,169 @@
+use super::super::super::board::Unit;
+use super::candidates::{combinations, Candidates};
+use super::{removals, value_names, Deduction, Technique};
+
+static NAMES: [&str; 3] = ["X-Wing", "Swordfish", "Jellyfish"];
+static SCORES: [f32; 3] = [3.2, 3.8, 5.2];
+
+/// Value that can only go in the same N columns on N rows (or vice versa).
+/// It can be removed from other cells of those columns.
+pub struct Fish {
+    size: usize,
+}
+
+impl Fish {
+    /// Creates fish of `size` lines: 2 (X-Wing), 3 (Swordfish) or 4 (Jellyfish).
+    pub fn new(size: usize) -> Fish {
+        assert!((2..=4).contains(&size), "Fish size must be 2-4");
+        Fish { size }
+    }
+
+    fn find_in_lines(
+        &self,
+        grid: &Candidates,
+        base: fn(usize) -> Unit,
+        cover: fn(usize) -> Unit,
+    ) -> Option<Deduction> {
+        for val in 1..10 {
+            // Positions along each base line, e.g. columns of a row
+            let positions: Vec<Vec<usize>> = (0..9)
+                .map(|line| {
+                    grid.positions(base(line), val)
+                        .iter()
+                        .map(|&index| position_on(base(line), index))
+                        .collect()
+                })
+                .collect();
+            let lines: Vec<usize> = (0..9)
+                .filter(|&line| positions[line].len() >= 2 && positions[line].len() <= self.size)
+                .collect();
+
+            for subset in combinations(&lines, self.size) {
+                let mut covers: Vec<usize> = subset
+                    .iter()
+                    .flat_map(|&line| positions[line].iter().cloned())
+                    .collect();
+                covers.sort();
+                covers.dedup();
+                if covers.len() != self.size {
+                    continue;
+                }
+
+                let base_cells: Vec<usize> = subset
+                    .iter()
+                    .flat_map(|&line| base(line).cells().to_vec())
+                    .collect();
+                let mut deduction = Deduction::new(self.name());
+                deduction.cells = base_cells
+                    .iter()
+                    .filter(|&&index| grid.has(index, val))
+                    .map(|&index| (index / 9, index % 9))
+                    .collect();
+                deduction.units = subset
+                    .iter()
+                    .map(|&line| base(line))
+                    .chain(covers.iter().map(|&line| cover(line)))
+                    .collect();
+
+                for &line in &covers {
+                    for &index in cover(line).cells().iter() {
+                        if !base_cells.contains(&index) && grid.has(index, val) {
+                            deduction.eliminations.push((index / 9, index % 9, val));
+                        }
+                    }
+                }
+                if !deduction.eliminations.is_empty() {
+                    let text = format!(
+                        "{} in {} can only go in {}, so remove {}",
+                        val,
+                        line_names(base, &subset),
+                        line_names(cover, &covers),
+                        removals(&deduction.eliminations)
+                    );
+                    return Some(deduction.explain(text));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Technique for Fish {
+    fn name(&self) -> &'static str {
+        NAMES[self.size - 2]
+    }
+
+    fn score(&self) -> f32 {
+        SCORES[self.size - 2]
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        self.find_in_lines(grid, Unit::Row, Unit::Col)
+            .or_else(|| self.find_in_lines(grid, Unit::Col, Unit::Row))
+    }
+}
+
+/// Describes lines, e.g. `rows 2, 5`.
+fn line_names(line: fn(usize) -> Unit, indices: &[usize]) -> String {
+    let kind = match line(0) {
+        Unit::Row(_) => "rows",
+        _ => "columns",
+    };
+    let numbers: Vec<usize> = indices.iter().map(|index| index + 1).collect();
+    format!("{} {}", kind, value_names(&numbers))
+}
+
+fn position_on(line: Unit, index: usize) -> usize {
+    match line {
+        Unit::Row(_) => index % 9,
+        _ => index / 9,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn when_value_is_in_two_columns_on_two_rows_then_find_x_wing() {
+        let mut grid = Candidates::empty();
+        // Value 7 only in columns 2 and 6 on rows 1 and 4
+        for &row in &[1, 4] {
+            for col in (0..9).filter(|&col| col != 2 && col != 6) {
+                grid.eliminate(row * 9 + col, 7);
+            }
+        }
+
+        let deduction = Fish::new(2).find(&grid).unwrap();
+        assert_eq!(deduction.technique, "X-Wing");
+        assert_eq!(deduction.cells, vec![(1, 2), (1, 6), (4, 2), (4, 6)]);
+        assert_eq!(deduction.eliminations.len(), 14);
+        assert!(deduction.eliminations.contains(&(0, 2, 7)));
+        assert!(!deduction.eliminations.contains(&(4, 6, 7)));
+        assert!(deduction
+            .explanation
+            .starts_with("X-Wing: 7 in rows 2, 5 can only go in columns 3, 7, so remove 7 from"));
+    }
+
+    #[test]
+    fn when_value_is_in_three_rows_on_three_columns_then_find_swordfish() {
+        let mut grid = Candidates::empty();
+        // Value 2 only in rows 0, 3 and 7 on columns 1, 4 and 8
+        let rows = [(0, 3), (3, 7), (0, 7)];
+        for (&col, &(a, b)) in [1, 4, 8].iter().zip(rows.iter()) {
+            for row in (0..9).filter(|&row| row != a && row != b) {
+                grid.eliminate(row * 9 + col, 2);
+            }
+        }
+
+        let deduction = Fish::new(3).find(&grid).unwrap();
+        assert_eq!(deduction.technique, "Swordfish");
+        assert_eq!(
+            deduction.units[..3],
+            [Unit::Col(1), Unit::Col(4), Unit::Col(8)]
+        );
+        assert_eq!(deduction.eliminations.len(), 18);
+        assert!(deduction.eliminations.contains(&(3, 0, 2)));
+    }
+}
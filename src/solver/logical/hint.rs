@@ -0,0 +1,83 @@
+use super::super::super::board::{Placement, Unit};
+use super::Deduction;
+
+/// Amount of information revealed by a `Hint`.
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+pub enum HintLevel {
+    /// Only the region to look at.
+    Region,
+    /// Region and technique(s) to use.
+    Technique,
+    /// Exact placement with explanation.
+    Placement,
+}
+
+/// Next logical placement for a board, with the steps leading to it.
+///
+/// Steps before the last one only eliminate candidates.
+/// The last step places a value.
+#[derive(Clone, Debug)]
+pub struct Hint {
+    steps: Vec<Deduction>,
+}
+
+impl Hint {
+    pub(super) fn new(steps: Vec<Deduction>) -> Hint {
+        Hint { steps }
+    }
+
+    /// Returns region to look at for the placement.
+    pub fn region(&self) -> Unit {
+        let last = self.last();
+        match last.units.first() {
+            Some(&unit) => unit,
+            None => {
+                let (row, col, _) = last.placements[0];
+                Unit::Box((row / 3) * 3 + col / 3)
+            }
+        }
+    }
+
+    /// Returns names of techniques needed, in order of use.
+    pub fn techniques(&self) -> Vec<&'static str> {
+        let mut result = Vec::new();
+        for step in &self.steps {
+            if !result.contains(&step.technique) {
+                result.push(step.technique);
+            }
+        }
+        result
+    }
+
+    /// Returns the placement the hint leads to.
+    pub fn placement(&self) -> Placement {
+        self.last().placements[0]
+    }
+
+    /// Returns steps leading to the placement.
+    pub fn steps(&self) -> &[Deduction] {
+        &self.steps
+    }
+
+    /// Describes hint, revealing only as much as specified by `level`.
+    pub fn describe(&self, level: HintLevel) -> String {
+        match level {
+            HintLevel::Region => format!("Look at {}", self.region()),
+            HintLevel::Technique => format!(
+                "Use {} in {}",
+                self.techniques().join(", then "),
+                self.region()
+            ),
+            HintLevel::Placement => self
+                .steps
+                .iter()
+                .map(|step| step.explanation.as_str())
+                .collect::<Vec<&str>>()
+                .join("\n"),
+        }
+    }
+
+    fn last(&self) -> &Deduction {
+        self.steps.last().unwrap()
+    }
+}
@@ -0,0 +1,169 @@
+use super::super::super::board::Unit;
+use super::candidates::Candidates;
+use super::{removals, Deduction, Technique};
+
+/// Value that can only go on one row or column within a box.
+/// It can be removed from the rest of that row or column.
+pub struct PointingCandidates;
+
+impl Technique for PointingCandidates {
+    fn name(&self) -> &'static str {
+        "Pointing candidates"
+    }
+
+    fn score(&self) -> f32 {
+        2.6
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        for the_box in (0..9).map(Unit::Box) {
+            for val in 1..10 {
+                let positions = grid.positions(the_box, val);
+                if positions.len() < 2 {
+                    continue;
+                }
+
+                let line = match line_of(&positions) {
+                    Some(line) => line,
+                    None => continue,
+                };
+                if let Some(deduction) =
+                    eliminate_outside(self.name(), grid, val, &positions, the_box, line)
+                {
+                    return Some(deduction);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Value that can only go within one box on a row or column.
+/// It can be removed from the rest of that box.
+pub struct BoxLineReduction;
+
+impl Technique for BoxLineReduction {
+    fn name(&self) -> &'static str {
+        "Box/line reduction"
+    }
+
+    fn score(&self) -> f32 {
+        2.8
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        let lines = (0..9).map(Unit::Row).chain((0..9).map(Unit::Col));
+        for line in lines {
+            for val in 1..10 {
+                let positions = grid.positions(line, val);
+                if positions.len() < 2 {
+                    continue;
+                }
+
+                let the_box = Unit::Box(box_of(positions[0]));
+                if positions
+                    .iter()
+                    .any(|&index| Unit::Box(box_of(index)) != the_box)
+                {
+                    continue;
+                }
+                if let Some(deduction) =
+                    eliminate_outside(self.name(), grid, val, &positions, line, the_box)
+                {
+                    return Some(deduction);
+                }
+            }
+        }
+        None
+    }
+}
+
+fn box_of(index: usize) -> usize {
+    (index / 27) * 3 + (index % 9) / 3
+}
+
+/// Returns row or column shared by all positions, if any.
+fn line_of(positions: &[usize]) -> Option<Unit> {
+    let (row, col) = (positions[0] / 9, positions[0] % 9);
+    if positions.iter().all(|&index| index / 9 == row) {
+        Some(Unit::Row(row))
+    } else if positions.iter().all(|&index| index % 9 == col) {
+        Some(Unit::Col(col))
+    } else {
+        None
+    }
+}
+
+/// Removes `val` from cells of `target` unit that are not in `source` unit.
+fn eliminate_outside(
+    name: &'static str,
+    grid: &Candidates,
+    val: u8,
+    positions: &[usize],
+    source: Unit,
+    target: Unit,
+) -> Option<Deduction> {
+    let source_cells = source.cells();
+    let mut deduction = Deduction::new(name)
+        .cells(positions)
+        .units(&[source, target]);
+
+    for &index in target.cells().iter() {
+        if !source_cells.contains(&index) && grid.has(index, val) {
+            deduction.eliminations.push((index / 9, index % 9, val));
+        }
+    }
+
+    if deduction.eliminations.is_empty() {
+        None
+    } else {
+        let text = format!(
+            "{} in {} can only go in {}, so remove {}",
+            val,
+            source,
+            target,
+            removals(&deduction.eliminations)
+        );
+        Some(deduction.explain(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn when_value_is_on_one_row_of_box_then_find_pointing_candidates() {
+        let mut grid = Candidates::empty();
+        // Value 5 only on top row of box 0
+        for index in &[9, 10, 11, 18, 19, 20] {
+            grid.eliminate(*index, 5);
+        }
+
+        let deduction = PointingCandidates.find(&grid).unwrap();
+        assert_eq!(deduction.units, vec![Unit::Box(0), Unit::Row(0)]);
+        assert_eq!(deduction.eliminations.len(), 6);
+        assert!(deduction.eliminations.contains(&(0, 8, 5)));
+        assert_eq!(
+            deduction.explanation,
+            "Pointing candidates: 5 in box 1 can only go in row 1, \
+             so remove 5 from r1c4, r1c5, r1c6, r1c7, r1c8, r1c9"
+        );
+    }
+
+    #[test]
+    fn when_value_is_in_one_box_of_row_then_find_box_line_reduction() {
+        let mut grid = Candidates::empty();
+        // Value 3 only in box 2 on row 1
+        for col in 0..6 {
+            grid.eliminate(9 + col, 3);
+        }
+
+        let deduction = BoxLineReduction.find(&grid).unwrap();
+        assert_eq!(deduction.units, vec![Unit::Row(1), Unit::Box(2)]);
+        assert_eq!(deduction.eliminations.len(), 6);
+        assert!(deduction.eliminations.contains(&(0, 6, 3)));
+        assert!(deduction.eliminations.contains(&(2, 8, 3)));
+    }
+}
@@ -0,0 +1,507 @@
+//! Solver using only techniques a human would use.
+
+mod candidates;
+mod fish;
+mod hint;
+mod intersections;
+mod singles;
+mod subsets;
+mod wings;
+
+pub use self::candidates::{mask_of, values_of, Candidates, Mask};
+pub use self::fish::Fish;
+pub use self::hint::{Hint, HintLevel};
+pub use self::intersections::{BoxLineReduction, PointingCandidates};
+pub use self::singles::{HiddenSingle, NakedSingle};
+pub use self::subsets::{HiddenSubset, NakedSubset};
+pub use self::wings::{XyWing, XyzWing};
+
+pub use super::super::board::cell_name;
+use super::super::board::{Placement, SudokuBoard, Unit};
+use super::super::error::Error;
+use super::bitboard::BitboardSolver;
+use super::{Contradiction, Solution, Solver, Verification};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::fmt;
+
+/// Deduction made by a `Technique` on a candidate grid.
+///
+/// Displays as its explanation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Deduction {
+    /// Name of technique that made the deduction.
+    pub technique: &'static str,
+    /// Values that can be placed.
+    pub placements: Vec<Placement>,
+    /// Candidates that can be removed, as `(row, col, val)`.
+    pub eliminations: Vec<Placement>,
+    /// Cells forming the pattern, as `(row, col)`.
+    pub cells: Vec<(usize, usize)>,
+    /// Units the pattern was found in.
+    pub units: Vec<Unit>,
+    /// Human-readable explanation, e.g.
+    /// `"Hidden single: 7 in row 3 can only go in r3c5"`.
+    pub explanation: String,
+}
+
+impl fmt::Display for Deduction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.explanation)
+    }
+}
+
+impl Deduction {
+    fn new(technique: &'static str) -> Deduction {
+        Deduction {
+            technique,
+            placements: Vec::new(),
+            eliminations: Vec::new(),
+            cells: Vec::new(),
+            units: Vec::new(),
+            explanation: String::new(),
+        }
+    }
+
+    fn cells(mut self, indices: &[usize]) -> Deduction {
+        self.cells = indices
+            .iter()
+            .map(|&index| (index / 9, index % 9))
+            .collect();
+        self
+    }
+
+    fn units(mut self, units: &[Unit]) -> Deduction {
+        self.units = units.to_vec();
+        self
+    }
+
+    /// Sets explanation, prefixed with technique name.
+    fn explain(mut self, text: String) -> Deduction {
+        self.explanation = format!("{}: {}", self.technique, text);
+        self
+    }
+
+    /// Applies placements and eliminations to candidate grid.
+    pub fn apply(&self, grid: &mut Candidates) {
+        for &(row, col, val) in &self.placements {
+            grid.place(row * 9 + col, val);
+        }
+        for &(row, col, val) in &self.eliminations {
+            grid.eliminate(row * 9 + col, val);
+        }
+    }
+}
+
+/// Solving technique that finds deductions on a candidate grid.
+pub trait Technique {
+    /// Name of the technique, e.g. `"Hidden single"`.
+    fn name(&self) -> &'static str;
+
+    /// Difficulty score of the technique, in the spirit of the
+    /// Sudoku Explainer scale, e.g. 1.5 for hidden single.
+    fn score(&self) -> f32;
+
+    /// Finds a deduction that places a value or eliminates
+    /// at least one candidate, or returns `None`.
+    fn find(&self, grid: &Candidates) -> Option<Deduction>;
+}
+
+/// Sudoku solver using human solving techniques.
+///
+/// # Remarks
+///
+/// The solver repeatedly applies the first technique in its catalog
+/// that finds a deduction, so techniques should be ordered from
+/// simplest to hardest. It never guesses: puzzles that cannot be
+/// completed with the techniques fail with `Error::GuessingRequired`.
+/// `verify` reports them as `Verification::GuessingRequired` when they
+/// have one solution, relying on `BitboardSolver` to tell.
+///
+/// The default catalog contains singles, intersections, naked and
+/// hidden subsets up to quads, X-Wing, Swordfish, Jellyfish,
+/// XY-Wing and XYZ-Wing.
+pub struct LogicalSolver {
+    techniques: Vec<Box<dyn Technique>>,
+    deductions: Vec<Deduction>,
+}
+
+impl Default for LogicalSolver {
+    fn default() -> LogicalSolver {
+        LogicalSolver::new()
+    }
+}
+
+impl Solver for LogicalSolver {
+    fn verify(&mut self, board: &SudokuBoard) -> Verification {
+        match self.solve(board) {
+            Ok(_) => Verification::ValidWithBranches(0),
+            Err(Error::InvalidBoard) => Verification::invalid_clues(board),
+            Err(Error::NoSolution) => Verification::Unsolvable(
+                self.find_contradiction(board)
+                    .unwrap_or(Contradiction::NoBranchLeft),
+            ),
+            Err(_) => match BitboardSolver::new().verify(board) {
+                Verification::ValidWithBranches(_) => Verification::GuessingRequired,
+                other => other,
+            },
+        }
+    }
+
+    fn solve(&mut self, board: &SudokuBoard) -> Result<Solution, Error> {
+        self.try_solve(board, None)
+    }
+
+    fn try_solve(
+        &mut self,
+        board: &SudokuBoard,
+        max_iterations: Option<u32>,
+    ) -> Result<Solution, Error> {
+        self.deductions.clear();
+
+        if !board.is_valid() {
+            return Err(Error::InvalidBoard);
+        }
+
+        let mut grid = Candidates::new(board);
+
+        while !grid.is_solved() {
+            if grid.has_contradiction() {
+                return Err(Error::NoSolution);
+            }
+            if let Some(max) = max_iterations {
+                if self.deductions.len() as u32 >= max {
+                    return Err(Error::IterationLimit(max));
+                }
+            }
+
+            let deduction = self.next_deduction(&grid).ok_or(Error::GuessingRequired)?;
+            deduction.apply(&mut grid);
+            self.deductions.push(deduction);
+        }
+
+        let mut solved = board.clone();
+        grid.apply_to(&mut solved);
+
+        Ok(Solution {
+            board: solved,
+            placements: self
+                .deductions
+                .iter()
+                .flat_map(|deduction| deduction.placements.iter().cloned())
+                .collect(),
+            branches: 0,
+            steps: self.deductions.clone(),
+        })
+    }
+}
+
+impl LogicalSolver {
+    /// Creates new `LogicalSolver` with the default technique catalog.
+    pub fn new() -> LogicalSolver {
+        LogicalSolver::with_techniques(vec![
+            Box::new(HiddenSingle),
+            Box::new(NakedSingle),
+            Box::new(PointingCandidates),
+            Box::new(BoxLineReduction),
+            Box::new(NakedSubset::new(2)),
+            Box::new(Fish::new(2)),
+            Box::new(HiddenSubset::new(2)),
+            Box::new(NakedSubset::new(3)),
+            Box::new(Fish::new(3)),
+            Box::new(HiddenSubset::new(3)),
+            Box::new(XyWing),
+            Box::new(XyzWing),
+            Box::new(NakedSubset::new(4)),
+            Box::new(Fish::new(4)),
+            Box::new(HiddenSubset::new(4)),
+        ])
+    }
+
+    /// Creates new `LogicalSolver` with specified techniques,
+    /// ordered from simplest to hardest.
+    pub fn with_techniques(techniques: Vec<Box<dyn Technique>>) -> LogicalSolver {
+        LogicalSolver {
+            techniques,
+            deductions: Vec::new(),
+        }
+    }
+
+    /// Adds technique to the end of the catalog.
+    pub fn technique<T>(mut self, technique: T) -> LogicalSolver
+    where
+        T: Technique + 'static,
+    {
+        self.techniques.push(Box::new(technique));
+        self
+    }
+
+    /// Returns score of technique with specified name,
+    /// or `None` if it is not in the catalog.
+    pub fn score_of(&self, name: &str) -> Option<f32> {
+        self.techniques
+            .iter()
+            .find(|technique| technique.name() == name)
+            .map(|technique| technique.score())
+    }
+
+    /// Returns deductions made during the last solve, in order.
+    pub fn deductions(&self) -> &[Deduction] {
+        &self.deductions
+    }
+
+    /// Returns names of techniques used during the last solve,
+    /// in order of first use.
+    pub fn techniques_used(&self) -> Vec<&'static str> {
+        let mut result = Vec::new();
+        for deduction in &self.deductions {
+            if !result.contains(&deduction.technique) {
+                result.push(deduction.technique);
+            }
+        }
+        result
+    }
+
+    /// Finds the simplest next placement for a partially filled board,
+    /// which may contain values entered by the player.
+    ///
+    /// Returns `None` if the board is filled, breaks Sudoku rules,
+    /// or no placement can be found with the techniques.
+    pub fn hint(&mut self, board: &SudokuBoard) -> Option<Hint> {
+        if !board.is_valid() {
+            return None;
+        }
+
+        let mut grid = Candidates::new(board);
+        let mut steps = Vec::new();
+
+        while !grid.is_solved() && !grid.has_contradiction() {
+            let deduction = self.next_deduction(&grid)?;
+            deduction.apply(&mut grid);
+
+            let is_placement = !deduction.placements.is_empty();
+            steps.push(deduction);
+            if is_placement {
+                return Some(Hint::new(steps));
+            }
+        }
+
+        None
+    }
+
+    /// Applies techniques to board until a contradiction shows up.
+    ///
+    /// Returns `None` if the board is solved or the techniques run out.
+    pub(crate) fn find_contradiction(&self, board: &SudokuBoard) -> Option<Contradiction> {
+        let mut grid = Candidates::new(board);
+
+        while !grid.is_solved() {
+            if let Some(contradiction) = grid.contradiction() {
+                return Some(contradiction);
+            }
+            self.next_deduction(&grid)?.apply(&mut grid);
+        }
+
+        None
+    }
+
+    fn next_deduction(&self, grid: &Candidates) -> Option<Deduction> {
+        self.techniques
+            .iter()
+            .filter_map(|technique| technique.find(grid))
+            .next()
+    }
+}
+
+fn cell_names(indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(|&index| cell_name(index / 9, index % 9))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn value_names<T: fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|val| val.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Describes eliminations grouped by value, e.g. `1 from r1c2, r1c3; 2 from r1c4`.
+fn removals(eliminations: &[Placement]) -> String {
+    let mut groups: Vec<(u8, Vec<usize>)> = Vec::new();
+    for &(row, col, val) in eliminations {
+        match groups.iter_mut().find(|(group_val, _)| *group_val == val) {
+            Some((_, cells)) => cells.push(row * 9 + col),
+            None => groups.push((val, vec![row * 9 + col])),
+        }
+    }
+    groups.sort_by_key(|(val, _)| *val);
+
+    groups
+        .iter()
+        .map(|(val, cells)| format!("{} from {}", val, cell_names(cells)))
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::least_options::LeastOptionsSolver;
+    use super::*;
+
+    static EASY: &str =
+        "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+
+    static X_WING: &str =
+        "1.....569492.561.8.561.924...964.8.1.64.1....218.356.4.4.5...169.5.614.2621.....5";
+
+    static AI_ESCARGOT: &str =
+        "1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..";
+
+    #[test]
+    fn solve_easy_with_singles() {
+        let board: SudokuBoard = EASY.parse().unwrap();
+        let mut solver = LogicalSolver::new();
+
+        let solution = solver.solve(&board).expect("Expected success");
+        assert!(solution.board.is_filled() && solution.board.is_valid());
+        assert_eq!(solution.branches, 0);
+        for name in solver.techniques_used() {
+            assert!(name == "Hidden single" || name == "Naked single");
+        }
+    }
+
+    #[test]
+    fn solve_x_wing_reports_technique() {
+        let board: SudokuBoard = X_WING.parse().unwrap();
+        let mut solver = LogicalSolver::new();
+
+        solver.solve(&board).expect("Expected success");
+        assert!(solver.techniques_used().contains(&"X-Wing"));
+    }
+
+    #[test]
+    fn solution_matches_backtracking_solver() {
+        let board: SudokuBoard = X_WING.parse().unwrap();
+
+        let logical = LogicalSolver::new().solve(&board).unwrap();
+        let backtracking = LeastOptionsSolver::new().solve(&board).unwrap();
+        assert_eq!(logical.board.to_line(), backtracking.board.to_line());
+    }
+
+    #[test]
+    fn solution_steps_explain_each_deduction() {
+        let board: SudokuBoard = X_WING.parse().unwrap();
+        let mut solver = LogicalSolver::new();
+
+        let solution = solver.solve(&board).unwrap();
+        assert_eq!(solution.steps.len(), solver.deductions().len());
+        for step in &solution.steps {
+            assert!(step.explanation.starts_with(step.technique));
+        }
+
+        let x_wing = solution
+            .steps
+            .iter()
+            .find(|step| step.technique == "X-Wing")
+            .unwrap();
+        assert!(x_wing.to_string().contains("can only go in columns"));
+    }
+
+    #[test]
+    fn when_describing_removals_then_group_by_value() {
+        let text = removals(&[(0, 1, 2), (0, 2, 1), (3, 3, 2)]);
+        assert_eq!(text, "1 from r1c3; 2 from r1c2, r4c4");
+    }
+
+    #[test]
+    fn hint_reveals_next_placement_progressively() {
+        let mut board: SudokuBoard = EASY.parse().unwrap();
+        let solution = LeastOptionsSolver::new().solve(&board).unwrap();
+        // Player has already filled in a few cells
+        for &index in &[0, 1, 3] {
+            board.values[index] = solution.board.values[index];
+        }
+
+        let hint = LogicalSolver::new().hint(&board).expect("Expected hint");
+        let (row, col, val) = hint.placement();
+        assert_eq!(board.values[row * 9 + col], 0);
+        assert_eq!(solution.board.values[row * 9 + col], val);
+
+        assert_eq!(
+            hint.describe(HintLevel::Region),
+            format!("Look at {}", hint.region())
+        );
+        assert!(hint
+            .describe(HintLevel::Technique)
+            .starts_with("Use Hidden single in "));
+        assert!(hint
+            .describe(HintLevel::Placement)
+            .ends_with(&cell_name(row, col)));
+    }
+
+    #[test]
+    fn hint_includes_eliminations_before_placement() {
+        let mut board: SudokuBoard = X_WING.parse().unwrap();
+        let solution = LogicalSolver::new().solve(&board).unwrap();
+        // Fill in all placements made before the X-Wing is needed
+        for step in solution
+            .steps
+            .iter()
+            .take_while(|step| step.technique != "X-Wing")
+        {
+            for &(row, col, val) in &step.placements {
+                board.values[row * 9 + col] = val;
+            }
+        }
+
+        let hint = LogicalSolver::new().hint(&board).unwrap();
+        let (last, eliminations) = hint.steps().split_last().unwrap();
+        assert!(hint.techniques().contains(&"X-Wing"));
+        assert!(eliminations.iter().all(|step| step.placements.is_empty()));
+        assert_eq!(last.placements.len(), 1);
+        let (row, col, val) = hint.placement();
+        assert_eq!(solution.board.values[row * 9 + col], val);
+    }
+
+    #[test]
+    fn hint_returns_none_for_filled_or_invalid_board() {
+        let board: SudokuBoard = EASY.parse().unwrap();
+        let solution = LogicalSolver::new().solve(&board).unwrap();
+        assert!(LogicalSolver::new().hint(&solution.board).is_none());
+
+        let mut invalid = board.clone();
+        invalid.values[0] = 3;
+        assert!(LogicalSolver::new().hint(&invalid).is_none());
+    }
+
+    #[test]
+    fn solve_fails_when_guessing_required() {
+        let board: SudokuBoard = AI_ESCARGOT.parse().unwrap();
+
+        match LogicalSolver::new().solve(&board) {
+            Err(Error::GuessingRequired) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            LogicalSolver::new().verify(&board),
+            Verification::GuessingRequired
+        );
+    }
+
+    #[test]
+    fn solve_fails_when_only_given_singles() {
+        let board: SudokuBoard = X_WING.parse().unwrap();
+        let mut solver =
+            LogicalSolver::with_techniques(vec![Box::new(HiddenSingle), Box::new(NakedSingle)]);
+
+        assert!(solver.solve(&board).is_err());
+        solver = solver.technique(Fish::new(2));
+        assert!(solver.solve(&board).is_ok());
+    }
+}
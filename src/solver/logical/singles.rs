@@ -0,0 +1,106 @@
+use super::super::super::board::Unit;
+use super::candidates::{values_of, Candidates};
+use super::{cell_name, Deduction, Technique};
+
+/// Cell with only one candidate left.
+pub struct NakedSingle;
+
+impl Technique for NakedSingle {
+    fn name(&self) -> &'static str {
+        "Naked single"
+    }
+
+    fn score(&self) -> f32 {
+        2.3
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        let index = (0..81).find(|&index| grid.count(index) == 1)?;
+        let val = values_of(grid.mask(index)).next()?;
+
+        let (row, col) = (index / 9, index % 9);
+        let mut deduction = Deduction::new(self.name()).cells(&[index]).explain(format!(
+            "{} can only be {}",
+            cell_name(row, col),
+            val
+        ));
+        deduction.placements.push((row, col, val));
+        Some(deduction)
+    }
+}
+
+/// Value that can only go in one cell of a row, column or box.
+pub struct HiddenSingle;
+
+impl Technique for HiddenSingle {
+    fn name(&self) -> &'static str {
+        "Hidden single"
+    }
+
+    fn score(&self) -> f32 {
+        1.5
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        for unit in Unit::all() {
+            for val in 1..10 {
+                if let [index] = grid.positions(unit, val)[..] {
+                    let (row, col) = (index / 9, index % 9);
+                    let mut deduction = Deduction::new(self.name())
+                        .cells(&[index])
+                        .units(&[unit])
+                        .explain(format!(
+                            "{} in {} can only go in {}",
+                            val,
+                            unit,
+                            cell_name(row, col)
+                        ));
+                    deduction.placements.push((row, col, val));
+                    return Some(deduction);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn when_cell_has_one_candidate_then_find_naked_single() {
+        let mut grid = Candidates::empty();
+        for val in (1..10).filter(|&val| val != 4) {
+            grid.eliminate(20, val);
+        }
+
+        let deduction = NakedSingle.find(&grid).unwrap();
+        assert_eq!(deduction.placements, vec![(2, 2, 4)]);
+        assert_eq!(deduction.explanation, "Naked single: r3c3 can only be 4");
+    }
+
+    #[test]
+    fn when_value_has_one_position_in_unit_then_find_hidden_single() {
+        let mut grid = Candidates::empty();
+        for col in (0..9).filter(|&col| col != 6) {
+            grid.eliminate(col, 9);
+        }
+
+        let deduction = HiddenSingle.find(&grid).unwrap();
+        assert_eq!(deduction.placements, vec![(0, 6, 9)]);
+        assert_eq!(deduction.units, vec![Unit::Row(0)]);
+        assert_eq!(
+            deduction.explanation,
+            "Hidden single: 9 in row 1 can only go in r1c7"
+        );
+    }
+
+    #[test]
+    fn when_no_singles_then_find_nothing() {
+        let grid = Candidates::empty();
+        assert!(NakedSingle.find(&grid).is_none());
+        assert!(HiddenSingle.find(&grid).is_none());
+    }
+}
@@ -0,0 +1,211 @@
+use super::super::super::board::Unit;
+use super::candidates::{combinations, mask_of, values_of, Candidates, Mask};
+use super::{cell_names, removals, value_names, Deduction, Technique};
+
+static NAKED_NAMES: [&str; 3] = ["Naked pair", "Naked triple", "Naked quad"];
+static NAKED_SCORES: [f32; 3] = [3.0, 3.6, 5.0];
+static HIDDEN_NAMES: [&str; 3] = ["Hidden pair", "Hidden triple", "Hidden quad"];
+static HIDDEN_SCORES: [f32; 3] = [3.4, 4.0, 5.4];
+
+/// N cells in a unit that together have only N candidates.
+/// The candidates can be removed from other cells of the unit.
+pub struct NakedSubset {
+    size: usize,
+}
+
+impl NakedSubset {
+    /// Creates naked subset of `size` cells: 2 (pair), 3 (triple) or 4 (quad).
+    pub fn new(size: usize) -> NakedSubset {
+        assert!((2..=4).contains(&size), "Subset size must be 2-4");
+        NakedSubset { size }
+    }
+}
+
+impl Technique for NakedSubset {
+    fn name(&self) -> &'static str {
+        NAKED_NAMES[self.size - 2]
+    }
+
+    fn score(&self) -> f32 {
+        NAKED_SCORES[self.size - 2]
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        for unit in Unit::all() {
+            let cells = unit.cells();
+            let open: Vec<usize> = cells
+                .iter()
+                .cloned()
+                .filter(|&index| grid.count(index) >= 2 && grid.count(index) <= self.size as u32)
+                .collect();
+
+            for subset in combinations(&open, self.size) {
+                let mask = subset
+                    .iter()
+                    .fold(0, |mask, &index| mask | grid.mask(index));
+                if mask.count_ones() as usize != self.size {
+                    continue;
+                }
+
+                let mut deduction = Deduction::new(self.name()).cells(&subset).units(&[unit]);
+                for &index in cells.iter().filter(|index| !subset.contains(index)) {
+                    for val in values_of(grid.mask(index) & mask) {
+                        deduction.eliminations.push((index / 9, index % 9, val));
+                    }
+                }
+                if !deduction.eliminations.is_empty() {
+                    let values: Vec<u8> = values_of(mask).collect();
+                    let text = format!(
+                        "{} in {} contain only {}, so remove {}",
+                        cell_names(&subset),
+                        unit,
+                        value_names(&values),
+                        removals(&deduction.eliminations)
+                    );
+                    return Some(deduction.explain(text));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// N values in a unit that can only go in the same N cells.
+/// Other candidates can be removed from those cells.
+pub struct HiddenSubset {
+    size: usize,
+}
+
+impl HiddenSubset {
+    /// Creates hidden subset of `size` values: 2 (pair), 3 (triple) or 4 (quad).
+    pub fn new(size: usize) -> HiddenSubset {
+        assert!((2..=4).contains(&size), "Subset size must be 2-4");
+        HiddenSubset { size }
+    }
+}
+
+impl Technique for HiddenSubset {
+    fn name(&self) -> &'static str {
+        HIDDEN_NAMES[self.size - 2]
+    }
+
+    fn score(&self) -> f32 {
+        HIDDEN_SCORES[self.size - 2]
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        for unit in Unit::all() {
+            let cells = unit.cells();
+            let open: Vec<usize> = (1..10)
+                .filter(|&val| {
+                    let count = grid.positions(unit, val as u8).len();
+                    count >= 2 && count <= self.size
+                })
+                .collect();
+
+            for subset in combinations(&open, self.size) {
+                let mask: Mask = subset
+                    .iter()
+                    .fold(0, |mask, &val| mask | mask_of(val as u8));
+                let positions: Vec<usize> = cells
+                    .iter()
+                    .cloned()
+                    .filter(|&index| grid.mask(index) & mask != 0)
+                    .collect();
+                if positions.len() != self.size {
+                    continue;
+                }
+
+                let mut deduction = Deduction::new(self.name()).cells(&positions).units(&[unit]);
+                for &index in &positions {
+                    for val in values_of(grid.mask(index) & !mask) {
+                        deduction.eliminations.push((index / 9, index % 9, val));
+                    }
+                }
+                if !deduction.eliminations.is_empty() {
+                    let text = format!(
+                        "{} in {} can only go in {}, so remove {}",
+                        value_names(&subset),
+                        unit,
+                        cell_names(&positions),
+                        removals(&deduction.eliminations)
+                    );
+                    return Some(deduction.explain(text));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn keep_only(grid: &mut Candidates, index: usize, vals: &[u8]) {
+        for val in (1..10).filter(|val| !vals.contains(val)) {
+            grid.eliminate(index, val);
+        }
+    }
+
+    #[test]
+    fn when_two_cells_share_two_candidates_then_find_naked_pair() {
+        let mut grid = Candidates::empty();
+        keep_only(&mut grid, 0, &[1, 2]);
+        keep_only(&mut grid, 5, &[1, 2]);
+
+        let deduction = NakedSubset::new(2).find(&grid).unwrap();
+        assert_eq!(deduction.technique, "Naked pair");
+        assert_eq!(deduction.cells, vec![(0, 0), (0, 5)]);
+        assert_eq!(deduction.units, vec![Unit::Row(0)]);
+        assert!(deduction.eliminations.contains(&(0, 8, 1)));
+        assert!(deduction.eliminations.contains(&(0, 1, 2)));
+        assert_eq!(deduction.eliminations.len(), 14);
+        assert!(deduction.explanation.starts_with(
+            "Naked pair: r1c1, r1c6 in row 1 contain only 1, 2, so remove 1 from r1c2"
+        ));
+    }
+
+    #[test]
+    fn when_three_cells_have_three_candidates_then_find_naked_triple() {
+        let mut grid = Candidates::empty();
+        keep_only(&mut grid, 0, &[1, 2]);
+        keep_only(&mut grid, 9, &[2, 3]);
+        keep_only(&mut grid, 18, &[1, 3]);
+
+        let deduction = NakedSubset::new(3).find(&grid).unwrap();
+        assert_eq!(deduction.technique, "Naked triple");
+        assert_eq!(deduction.units, vec![Unit::Col(0)]);
+        assert_eq!(deduction.eliminations.len(), 18);
+    }
+
+    #[test]
+    fn when_two_values_share_two_cells_then_find_hidden_pair() {
+        let mut grid = Candidates::empty();
+        for col in 2..9 {
+            grid.eliminate(col, 8);
+            grid.eliminate(col, 9);
+        }
+
+        let deduction = HiddenSubset::new(2).find(&grid).unwrap();
+        assert_eq!(deduction.technique, "Hidden pair");
+        assert_eq!(deduction.cells, vec![(0, 0), (0, 1)]);
+        assert_eq!(deduction.eliminations.len(), 14);
+        assert!(deduction.eliminations.iter().all(|&(_, _, val)| val < 8));
+    }
+
+    #[test]
+    fn when_no_candidates_removed_then_find_nothing() {
+        let mut grid = Candidates::empty();
+        for index in 0..9 {
+            keep_only(
+                &mut grid,
+                index,
+                &[(index + 1) as u8, ((index + 1) % 9 + 1) as u8],
+            );
+        }
+
+        assert!(NakedSubset::new(2).find(&grid).is_none());
+    }
+}
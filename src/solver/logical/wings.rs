@@ -0,0 +1,172 @@
+use super::super::super::board::are_peers;
+use super::candidates::{values_of, Candidates};
+use super::{cell_name, removals, Deduction, Technique};
+
+/// Pivot cell with candidates XY seeing two pincers with XZ and YZ.
+/// Z can be removed from cells that see both pincers.
+pub struct XyWing;
+
+impl Technique for XyWing {
+    fn name(&self) -> &'static str {
+        "XY-Wing"
+    }
+
+    fn score(&self) -> f32 {
+        4.2
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        for pivot in (0..81).filter(|&index| grid.count(index) == 2) {
+            let pincers: Vec<usize> = peers_with_count(grid, pivot, 2)
+                .into_iter()
+                .filter(|&index| (grid.mask(index) & grid.mask(pivot)).count_ones() == 1)
+                .collect();
+
+            for (i, &first) in pincers.iter().enumerate() {
+                for &second in &pincers[i + 1..] {
+                    let shared = grid.mask(first) & grid.mask(second);
+                    // Pincers share Z, and between them cover both X and Y
+                    if shared.count_ones() != 1
+                        || shared & grid.mask(pivot) != 0
+                        || (grid.mask(first) | grid.mask(second)) & grid.mask(pivot)
+                            != grid.mask(pivot)
+                    {
+                        continue;
+                    }
+
+                    let z = values_of(shared).next()?;
+                    if let Some(deduction) = eliminate_seen(
+                        self.name(),
+                        grid,
+                        z,
+                        &[pivot, first, second],
+                        &[first, second],
+                    ) {
+                        return Some(deduction);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Pivot cell with candidates XYZ seeing two pincers with XZ and YZ.
+/// Z can be removed from cells that see the pivot and both pincers.
+pub struct XyzWing;
+
+impl Technique for XyzWing {
+    fn name(&self) -> &'static str {
+        "XYZ-Wing"
+    }
+
+    fn score(&self) -> f32 {
+        4.4
+    }
+
+    fn find(&self, grid: &Candidates) -> Option<Deduction> {
+        for pivot in (0..81).filter(|&index| grid.count(index) == 3) {
+            let pincers: Vec<usize> = peers_with_count(grid, pivot, 2)
+                .into_iter()
+                .filter(|&index| grid.mask(index) & !grid.mask(pivot) == 0)
+                .collect();
+
+            for (i, &first) in pincers.iter().enumerate() {
+                for &second in &pincers[i + 1..] {
+                    let shared = grid.mask(first) & grid.mask(second);
+                    if shared.count_ones() != 1 {
+                        continue;
+                    }
+
+                    let z = values_of(shared).next()?;
+                    let cells = [pivot, first, second];
+                    if let Some(deduction) = eliminate_seen(self.name(), grid, z, &cells, &cells) {
+                        return Some(deduction);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn peers_with_count(grid: &Candidates, index: usize, count: u32) -> Vec<usize> {
+    (0..81)
+        .filter(|&peer| peer != index && are_peers(index, peer) && grid.count(peer) == count)
+        .collect()
+}
+
+/// Removes `val` from cells that see all `seen` cells, except pattern `cells`.
+fn eliminate_seen(
+    name: &'static str,
+    grid: &Candidates,
+    val: u8,
+    cells: &[usize],
+    seen: &[usize],
+) -> Option<Deduction> {
+    let mut deduction = Deduction::new(name).cells(cells);
+
+    for index in (0..81).filter(|index| !cells.contains(index)) {
+        if grid.has(index, val) && seen.iter().all(|&other| are_peers(index, other)) {
+            deduction.eliminations.push((index / 9, index % 9, val));
+        }
+    }
+
+    if deduction.eliminations.is_empty() {
+        None
+    } else {
+        let name = |index: usize| cell_name(index / 9, index % 9);
+        let text = format!(
+            "pivot {} with pincers {} and {}, one of them must be {}, so remove {}",
+            name(cells[0]),
+            name(cells[1]),
+            name(cells[2]),
+            val,
+            removals(&deduction.eliminations)
+        );
+        Some(deduction.explain(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn keep_only(grid: &mut Candidates, index: usize, vals: &[u8]) {
+        for val in (1..10).filter(|val| !vals.contains(val)) {
+            grid.eliminate(index, val);
+        }
+    }
+
+    #[test]
+    fn when_pivot_sees_two_pincers_then_find_xy_wing() {
+        let mut grid = Candidates::empty();
+        // Pivot r1c1 {1,2}, pincers r1c7 {1,3} and r5c1 {2,3}
+        keep_only(&mut grid, 0, &[1, 2]);
+        keep_only(&mut grid, 6, &[1, 3]);
+        keep_only(&mut grid, 36, &[2, 3]);
+
+        let deduction = XyWing.find(&grid).unwrap();
+        assert_eq!(deduction.cells, vec![(0, 0), (0, 6), (4, 0)]);
+        assert_eq!(deduction.eliminations, vec![(4, 6, 3)]);
+        assert_eq!(
+            deduction.explanation,
+            "XY-Wing: pivot r1c1 with pincers r1c7 and r5c1, \
+             one of them must be 3, so remove 3 from r5c7"
+        );
+    }
+
+    #[test]
+    fn when_pivot_has_three_candidates_then_find_xyz_wing() {
+        let mut grid = Candidates::empty();
+        // Pivot r1c2 {1,2,3}, pincers r1c8 {1,3} and r2c1 {2,3}
+        keep_only(&mut grid, 1, &[1, 2, 3]);
+        keep_only(&mut grid, 7, &[1, 3]);
+        keep_only(&mut grid, 9, &[2, 3]);
+
+        let deduction = XyzWing.find(&grid).unwrap();
+        assert_eq!(deduction.cells, vec![(0, 1), (0, 7), (1, 0)]);
+        assert_eq!(deduction.eliminations, vec![(0, 0, 3), (0, 2, 3)]);
+    }
+}
@@ -1,26 +1,160 @@
+pub mod bitboard;
+pub mod dlx;
 pub mod least_options;
+pub mod logical;
 
-use super::board::{Placement, SudokuBoard};
+use self::logical::Deduction;
+use super::board::{Placement, SudokuBoard, Unit};
+use super::error::Error;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Solution for Sudoku puzzle.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Solution {
     pub board: SudokuBoard,
     pub placements: Vec<Placement>,
     pub branches: u32,
+    /// Explained steps leading to the solution.
+    ///
+    /// Empty for solvers that do not explain their reasoning,
+    /// such as backtracking solvers. Not serialized, so deserialized
+    /// solutions have no steps.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub steps: Vec<Deduction>,
 }
 
 /// Represents Sudoku puzzle solver verification result.
-#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Verification {
-    /// Sudoku puzzle that is not valid, i.e. puzzle
-    /// that has no solution or many solutions.
-    NotValid,
+    /// Sudoku puzzle with clues breaking Sudoku rules,
+    /// listing every pair of conflicting clues.
+    InvalidClues(Vec<(Placement, Placement)>),
+    /// Sudoku puzzle that has no solution.
+    Unsolvable(Contradiction),
+    /// Sudoku puzzle that has more than one solution,
+    /// with two of them and the cells where they differ.
+    MultipleSolutions {
+        first: Box<SudokuBoard>,
+        second: Box<SudokuBoard>,
+        differences: Vec<(usize, usize)>,
+    },
+    /// Sudoku puzzle with one solution that the solver cannot
+    /// find without guessing. Only reported by solvers that
+    /// never guess, such as `LogicalSolver`.
+    GuessingRequired,
     /// Valid sudoku puzzle with specified number of
     /// branches in solution.
     ValidWithBranches(u32),
 }
 
+impl Verification {
+    /// Returns true if puzzle has one and only one solution.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Verification::ValidWithBranches(_))
+    }
+
+    /// Reports conflicting clues of board that breaks Sudoku rules.
+    pub(crate) fn invalid_clues(board: &SudokuBoard) -> Verification {
+        Verification::InvalidClues(board.conflicting_pairs())
+    }
+
+    /// Reports first contradiction found by logic on board without solution.
+    pub(crate) fn unsolvable(board: &SudokuBoard) -> Verification {
+        let contradiction = logical::LogicalSolver::new()
+            .find_contradiction(board)
+            .unwrap_or(Contradiction::NoBranchLeft);
+        Verification::Unsolvable(contradiction)
+    }
+
+    /// Reports two distinct solutions of board.
+    pub(crate) fn multiple_solutions(first: SudokuBoard, second: SudokuBoard) -> Verification {
+        let differences = (0..81)
+            .filter(|&index| first.values[index] != second.values[index])
+            .map(|index| (index / 9, index % 9))
+            .collect();
+        Verification::MultipleSolutions {
+            first: Box::new(first),
+            second: Box::new(second),
+            differences,
+        }
+    }
+}
+
+/// Reason a Sudoku puzzle has no solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Contradiction {
+    /// Empty cell where no value can be placed.
+    NoCandidates { row: usize, col: usize },
+    /// Value missing from a row, column or box
+    /// that cannot be placed in any of its cells.
+    NoPlacement { unit: Unit, value: u8 },
+    /// Every branch of the search ends in a contradiction,
+    /// none of which can be reached without guessing.
+    NoBranchLeft,
+}
+
+/// Returns iterator lazily enumerating every solution of `SudokuBoard`.
+pub fn solutions(board: &SudokuBoard) -> least_options::Solutions {
+    least_options::LeastOptionsSolver::new().solutions(board)
+}
+
+/// Returns true if the clues of `SudokuBoard` have a unique solution
+/// and every clue is needed for it, i.e. removing any one clue leaves
+/// a board with multiple solutions. Values that are not clues are ignored.
+pub fn is_minimal(board: &SudokuBoard, solver: &mut dyn Solver) -> bool {
+    let mut puzzle = board.clone();
+    for (value, &clue) in puzzle.values.iter_mut().zip(board.clues.iter()) {
+        if !clue {
+            *value = 0;
+        }
+    }
+    match solver.verify(&puzzle) {
+        Verification::ValidWithBranches(_) | Verification::GuessingRequired => (),
+        _ => return false,
+    }
+
+    (0..81).filter(|&index| puzzle.clues[index]).all(|index| {
+        let mut reduced = puzzle.clone();
+        reduced.values[index] = 0;
+        reduced.clues[index] = false;
+        matches!(
+            solver.verify(&reduced),
+            Verification::MultipleSolutions { .. }
+        )
+    })
+}
+
+/// Number of solutions of a board, counted up to a limit.
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SolutionCount {
+    /// Board has exactly this many solutions.
+    Exactly(u32),
+    /// Board has at least this many solutions (the limit was reached).
+    AtLeast(u32),
+}
+
+impl SolutionCount {
+    fn new(count: u32, limit: u32) -> SolutionCount {
+        if count >= limit {
+            SolutionCount::AtLeast(limit)
+        } else {
+            SolutionCount::Exactly(count)
+        }
+    }
+
+    /// Returns number of solutions found.
+    pub fn count(self) -> u32 {
+        match self {
+            SolutionCount::Exactly(count) | SolutionCount::AtLeast(count) => count,
+        }
+    }
+}
+
 /// Sudoku puzzle solver.
 pub trait Solver {
     /// Verifies that a `SudokuBoard` represents a valid Sudoku puzzle.
@@ -29,15 +163,240 @@ pub trait Solver {
     /// A valid puzzle is a puzzle that has one and only one solution.
     fn verify(&mut self, board: &SudokuBoard) -> Verification;
 
+    /// Counts solutions of `SudokuBoard`, stopping at `limit`.
+    ///
+    /// The default implementation branches on the empty cell with fewest
+    /// allowed values until `verify` finds each board valid or `solve`
+    /// finds it has no solution.
+    fn count_solutions(&mut self, board: &SudokuBoard, limit: u32) -> SolutionCount {
+        let mut count = 0;
+        let mut boards = vec![board.clone()];
+
+        while let Some(board) = boards.pop() {
+            if count >= limit {
+                break;
+            }
+            if self.verify(&board).is_valid() {
+                count += 1;
+                continue;
+            }
+            match self.solve(&board) {
+                Err(Error::NoSolution) | Err(Error::InvalidBoard) => (),
+                _ => boards.extend(branch(&board)),
+            }
+        }
+
+        SolutionCount::new(count, limit)
+    }
+
     /// Solves `SudokuBoard` and returns the solution,
-    /// or returns `Err(String)` if not solvable.
-    fn solve(&mut self, board: &SudokuBoard) -> Result<Solution, String>;
+    /// or returns `Err(Error)` if not solvable.
+    fn solve(&mut self, board: &SudokuBoard) -> Result<Solution, Error>;
 
     /// Tries to solve `SudokuBoard` within `max_iterations`,
-    /// or returns `Err(String)` if not solvable within specified number of iterations.
+    /// or returns `Err(Error::IterationLimit)` if not solvable within
+    /// specified number of iterations.
     fn try_solve(
         &mut self,
         board: &SudokuBoard,
         max_iterations: Option<u32>,
-    ) -> Result<Solution, String>;
+    ) -> Result<Solution, Error>;
+}
+
+/// Returns boards with each allowed value placed in the empty cell
+/// with fewest allowed values.
+fn branch(board: &SudokuBoard) -> Vec<SudokuBoard> {
+    let allowed_count = |index: usize| {
+        board
+            .get_allowed_vals(index / 9, index % 9)
+            .iter()
+            .filter(|&&allowed| allowed == 1)
+            .count()
+    };
+    let index = match (0..81)
+        .filter(|&index| board.values[index] == 0)
+        .min_by_key(|&index| allowed_count(index))
+    {
+        Some(index) => index,
+        None => return Vec::new(),
+    };
+
+    let allowed = board.get_allowed_vals(index / 9, index % 9);
+    (0..9)
+        .filter(|&val| allowed[val] == 1)
+        .map(|val| {
+            let mut next = board.clone();
+            next.values[index] = (val + 1) as u8;
+            next
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::bitboard::BitboardSolver;
+    use super::dlx::DlxSolver;
+    use super::least_options::LeastOptionsSolver;
+    use super::logical::LogicalSolver;
+    use super::*;
+
+    static SOLVED: &str =
+        "123456789456789123789123456214365897365897214897214365531642978642978531978531642";
+
+    fn solvers() -> Vec<Box<dyn Solver>> {
+        vec![
+            Box::new(LeastOptionsSolver::new()),
+            Box::new(DlxSolver::new()),
+            Box::new(BitboardSolver::new()),
+            Box::new(LogicalSolver::new()),
+        ]
+    }
+
+    #[test]
+    fn verify_reports_cells_that_differ_between_solutions() {
+        let line = format!("..{}..{}", &SOLVED[2..27], &SOLVED[29..]);
+        let board: SudokuBoard = line.parse().unwrap();
+
+        for mut solver in solvers() {
+            match solver.verify(&board) {
+                Verification::MultipleSolutions {
+                    first,
+                    second,
+                    differences,
+                } => {
+                    assert_eq!(differences, vec![(0, 0), (0, 1), (3, 0), (3, 1)]);
+                    assert_ne!(first, second);
+                    assert!(first.is_valid() && first.is_filled());
+                    assert!(second.is_valid() && second.is_filled());
+                }
+                other => panic!("Unexpected result: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn verify_reports_conflicting_clues() {
+        let board = SudokuBoard::with_clues(&[(0, 0, 1), (0, 1, 1), (8, 8, 1)]);
+
+        for mut solver in solvers() {
+            assert_eq!(
+                solver.verify(&board),
+                Verification::InvalidClues(vec![((0, 0, 1), (0, 1, 1))])
+            );
+        }
+    }
+
+    #[test]
+    fn verify_reports_first_contradiction() {
+        let board: SudokuBoard =
+            ".23456789.........1.......................................................1......"
+                .parse()
+                .unwrap();
+
+        // Backtracking would try every completion of the other cells
+        for mut solver in solvers().into_iter().skip(1) {
+            assert_eq!(
+                solver.verify(&board),
+                Verification::Unsolvable(Contradiction::NoCandidates { row: 0, col: 0 })
+            );
+        }
+    }
+
+    #[test]
+    fn is_minimal_only_if_every_clue_is_needed() {
+        // 17 clue puzzles are minimal, as there are none with 16 clues
+        let minimal: SudokuBoard =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......"
+                .parse()
+                .unwrap();
+        let solution = DlxSolver::new().solve(&minimal).unwrap().board;
+        let mut redundant = minimal.clone();
+        redundant.place((0, 1, solution.values[1])).unwrap();
+        redundant.clues[1] = true;
+
+        // Backtracking is slow on boards with 16 clues
+        for mut solver in solvers().into_iter().skip(1) {
+            assert!(is_minimal(&minimal, &mut *solver));
+            assert!(!is_minimal(&redundant, &mut *solver));
+        }
+    }
+
+    #[test]
+    fn is_minimal_ignores_values_that_are_not_clues() {
+        let mut board: SudokuBoard =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......"
+                .parse()
+                .unwrap();
+        let solution = DlxSolver::new().solve(&board).unwrap().board;
+        board.place((0, 1, solution.values[1])).unwrap();
+
+        assert!(is_minimal(&board, &mut BitboardSolver::new()));
+    }
+
+    #[test]
+    fn is_minimal_is_false_for_board_with_multiple_solutions() {
+        let line = format!("..{}..{}", &SOLVED[2..27], &SOLVED[29..]);
+        let board: SudokuBoard = line.parse().unwrap();
+
+        assert!(!is_minimal(&board, &mut BitboardSolver::new()));
+    }
+
+    #[test]
+    fn count_solutions_of_board_with_two_solutions() {
+        // Rectangle of 1s and 2s that can be swapped
+        let line = format!("..{}..{}", &SOLVED[2..27], &SOLVED[29..]);
+        let board: SudokuBoard = line.parse().unwrap();
+
+        let count = DlxSolver::new().count_solutions(&board, 10);
+        assert_eq!(count, SolutionCount::Exactly(2));
+        assert_eq!(count.count(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn when_serializing_solution_then_round_trip_without_steps() {
+        let board: SudokuBoard = format!("..{}", &SOLVED[2..]).parse().unwrap();
+        let solution = LogicalSolver::new().solve(&board).unwrap();
+        assert!(!solution.steps.is_empty());
+
+        let json = serde_json::to_string(&solution).unwrap();
+        assert!(!json.contains("steps"));
+
+        let result: Solution = serde_json::from_str(&json).unwrap();
+        assert_eq!(result.board, solution.board);
+        assert_eq!(result.placements, solution.placements);
+        assert_eq!(result.branches, solution.branches);
+        assert!(result.steps.is_empty());
+    }
+
+    #[test]
+    fn count_solutions_stops_at_limit() {
+        let board: SudokuBoard = format!("{}{}", &SOLVED[..27], ".".repeat(54))
+            .parse()
+            .unwrap();
+
+        let count = LogicalSolver::new().count_solutions(&board, 3);
+        assert_eq!(count, SolutionCount::AtLeast(3));
+    }
+
+    #[test]
+    fn count_solutions_of_board_without_solution() {
+        let board: SudokuBoard =
+            ".23456789.........1.......................................................1......"
+                .parse()
+                .unwrap();
+
+        let count = LogicalSolver::new().count_solutions(&board, 10);
+        assert_eq!(count, SolutionCount::Exactly(0));
+    }
+
+    #[test]
+    fn count_solutions_with_solver_that_does_not_guess() {
+        let line = format!("..{}..{}", &SOLVED[2..27], &SOLVED[29..]);
+        let board: SudokuBoard = line.parse().unwrap();
+
+        let count = LogicalSolver::new().count_solutions(&board, 10);
+        assert_eq!(count, SolutionCount::Exactly(2));
+    }
 }